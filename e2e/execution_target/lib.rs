@@ -0,0 +1,85 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+//! Stand-in for the contracts treasury governance calls out to, used by the
+//! end-to-end tests. It records execution hook notifications and, as an
+//! execution call target, calls back into the governance contract.
+
+pub use self::execution_target::ExecutionTarget;
+
+#[ink::contract]
+mod execution_target {
+    use ink::prelude::vec::Vec;
+
+    #[ink(storage)]
+    pub struct ExecutionTarget {
+        /// Proposal ids the hook was notified of, in order
+        notified: Vec<u32>,
+        /// Whether `on_proposal_executed` should fail
+        reject_notifications: bool,
+        /// Results `reenter` got back from the governance contract, oldest
+        /// first, with each governance error as its variant index
+        reentry_results: Vec<Result<(), u8>>,
+    }
+
+    impl ExecutionTarget {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                notified: Vec::new(),
+                reject_notifications: false,
+                reentry_results: Vec::new(),
+            }
+        }
+
+        /// Execution hook called by the governance contract
+        #[ink(message)]
+        pub fn on_proposal_executed(&mut self, proposal_id: u32) {
+            if self.reject_notifications {
+                panic!("notifications rejected");
+            }
+            self.notified.push(proposal_id);
+        }
+
+        /// Make later hook notifications fail
+        #[ink(message)]
+        pub fn set_reject_notifications(&mut self, reject: bool) {
+            self.reject_notifications = reject;
+        }
+
+        /// Get the proposal ids the hook was notified of
+        #[ink(message)]
+        pub fn get_notified(&self) -> Vec<u32> {
+            self.notified.clone()
+        }
+
+        /// Execution call target that calls a `(proposal_id: u32) -> Result<()>`
+        /// message, such as `execute_proposal`, back on the governance contract
+        /// executing it
+        #[ink(message)]
+        pub fn reenter(&mut self, governance: Address, selector: [u8; 4], proposal_id: u32) {
+            use ink::env::call::{build_call, ExecutionInput, Selector};
+
+            let result = build_call::<ink::env::DefaultEnvironment>()
+                .call(governance)
+                .exec_input(ExecutionInput::new(Selector::new(selector)).push_arg(proposal_id))
+                .returns::<Result<(), u8>>()
+                .try_invoke();
+            match result {
+                Ok(Ok(result)) => self.reentry_results.push(result),
+                _ => panic!("call back into governance failed"),
+            }
+        }
+
+        /// Get the results of the `reenter` call backs, oldest first
+        #[ink(message)]
+        pub fn get_reentry_results(&self) -> Vec<Result<(), u8>> {
+            self.reentry_results.clone()
+        }
+    }
+
+    impl Default for ExecutionTarget {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+}
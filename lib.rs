@@ -2,12 +2,16 @@
 
 mod test;
 
+// The generated message dispatch enum is dominated by `create_proposal`'s
+// arguments; boxing them is not possible across the ABI
+#[allow(clippy::large_enum_variant)]
 #[ink::contract]
 mod treasury_governance {
     use ink::prelude::vec::Vec;
     use ink::prelude::string::String;
-    use ink::storage::Mapping;
+    use ink::storage::{Lazy, Mapping};
     use ink::primitives::H160;
+    use ink::U256;
 
     /// Proposal Types
     #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, scale_info::TypeInfo)]
@@ -27,8 +31,38 @@ mod treasury_governance {
         SevenDays,
         FourteenDays,
         ThirtyDays,
+        /// Raw number of blocks, within the custom period bounds
+        Custom(u32),
     }
 
+    /// Shortest custom voting period: one hour
+    pub const MIN_CUSTOM_VOTING_PERIOD: u32 = 60 * 10;
+    /// Longest custom voting period: ninety days
+    pub const MAX_CUSTOM_VOTING_PERIOD: u32 = 90 * 24 * 60 * 10;
+
+    /// Most blocks a proposal's voting period may be extended by in total: seven days
+    pub const MAX_VOTING_EXTENSION: u32 = 7 * 24 * 60 * 10;
+
+    /// Blocks past its execution time before a queue head that may still
+    /// execute can be expired: seven days
+    pub const QUEUE_EXPIRY_GRACE_PERIOD: u32 = 7 * 24 * 60 * 10;
+
+    /// Lock length that earns one extra conviction multiple: seven days
+    pub const CONVICTION_PERIOD: u32 = 7 * 24 * 60 * 10;
+    /// Highest conviction multiplier
+    pub const MAX_CONVICTION_MULTIPLIER: u128 = 6;
+    /// Longest conviction lock: the shortest lock that earns the highest
+    /// multiplier, as a longer one would add no weight
+    pub const MAX_CONVICTION_LOCK: u32 = (MAX_CONVICTION_MULTIPLIER as u32 - 1) * CONVICTION_PERIOD;
+
+    /// Longest proposal title, in bytes
+    pub const MAX_TITLE_LENGTH: usize = 128;
+    /// Longest proposal description, in bytes
+    pub const MAX_DESCRIPTION_LENGTH: usize = 2048;
+
+    /// Longest voting option label, in bytes
+    pub const MAX_OPTION_LENGTH: usize = 64;
+
     impl VotingPeriod {
         /// Convert voting period to block numbers
         pub fn to_blocks(&self) -> u32 {
@@ -37,6 +71,7 @@ mod treasury_governance {
                 VotingPeriod::SevenDays => 7 * 24 * 60 * 10,
                 VotingPeriod::FourteenDays => 14 * 24 * 60 * 10,
                 VotingPeriod::ThirtyDays => 30 * 24 * 60 * 10,
+                VotingPeriod::Custom(blocks) => *blocks,
             }
         }
     }
@@ -49,6 +84,8 @@ mod treasury_governance {
         Ten,
         Twenty,
         TwentyFive,
+        /// Raw percentage, from 1 to 100
+        Custom(u32),
     }
 
     impl QuorumThreshold {
@@ -59,6 +96,7 @@ mod treasury_governance {
                 QuorumThreshold::Ten => 10,
                 QuorumThreshold::Twenty => 20,
                 QuorumThreshold::TwentyFive => 25,
+                QuorumThreshold::Custom(percentage) => *percentage,
             }
         }
     }
@@ -71,8 +109,16 @@ mod treasury_governance {
         OneDay,
         TwoDays,
         SevenDays,
+        /// Raw number of blocks, up to `MAX_CUSTOM_EXECUTION_DELAY`
+        Custom(u32),
     }
 
+    /// Most proposals `refresh_all_statuses` scans in one call
+    pub const MAX_REFRESH_BATCH: u32 = 50;
+
+    /// Longest custom execution delay: thirty days
+    pub const MAX_CUSTOM_EXECUTION_DELAY: u32 = 30 * 24 * 60 * 10;
+
     impl ExecutionDelay {
         /// Convert execution delay to block numbers
         pub fn to_blocks(&self) -> u32 {
@@ -81,10 +127,55 @@ mod treasury_governance {
                 ExecutionDelay::OneDay => 24 * 60 * 10, // 1 day * 24 hours * 60 minutes * 10 blocks per minute
                 ExecutionDelay::TwoDays => 2 * 24 * 60 * 10,
                 ExecutionDelay::SevenDays => 7 * 24 * 60 * 10,
+                ExecutionDelay::Custom(blocks) => *blocks,
             }
         }
     }
 
+    /// How ballots are cast and tallied
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, scale_info::TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum VotingMode {
+        /// One option per voter, counted with the voter's weight
+        Linear,
+        /// Voters may approve several options, each receiving their weight.
+        /// Quorum counts each voter's weight once.
+        Approval,
+        /// Voters spend credits on one option, which receives the integer
+        /// square root of the credits spent. Delegation does not apply:
+        /// delegators vote with their own credits.
+        Quadratic,
+        /// Voters rank options in order of preference. Tallies show first
+        /// preferences; the winner is decided by instant runoff.
+        RankedChoice,
+    }
+
+    /// Which events the contract emits
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, scale_info::TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum EventLevel {
+        /// No events
+        Off,
+        /// Proposal creation, execution and ownership changes only
+        Critical,
+        /// Every event
+        All,
+    }
+
+    /// Permissions that can be granted to accounts besides the owner
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode, scale_info::TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum Role {
+        /// Grant and revoke every role except Admin
+        Admin,
+        /// Create proposals regardless of the proposal threshold
+        Proposer,
+        /// Execute proposals when execution is restricted
+        Executor,
+        /// Pause and resume the contract
+        Pauser,
+    }
+
     /// Governance Parameters
     #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, scale_info::TypeInfo)]
     #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
@@ -92,6 +183,19 @@ mod treasury_governance {
         pub voting_period: VotingPeriod,
         pub quorum_threshold: QuorumThreshold,
         pub execution_delay: ExecutionDelay,
+        /// Blocks after `execution_time` during which a passed proposal may
+        /// still be executed (0 = no limit)
+        pub execution_window_blocks: u32,
+        /// Minimum total vote weight that must participate, on top of the
+        /// percentage quorum (0 = no minimum)
+        pub min_absolute_weight: u128,
+        /// Whether the treasury payout may be amended after passing
+        pub allow_payload_amendment: bool,
+        pub voting_mode: VotingMode,
+        /// Share of participating weight, in basis points, that option 0
+        /// ("approve") must exceed to pass; it must also lead outright.
+        /// 0 keeps plain plurality.
+        pub min_approval_bps: u32,
     }
 
     /// Voting Options
@@ -109,6 +213,57 @@ mod treasury_governance {
         pub option_text: String,
     }
 
+    /// Option index recorded on an abstaining vote
+    pub const ABSTAIN_INDEX: u32 = u32::MAX;
+
+    /// Why a proposal was rejected
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, scale_info::TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum RejectionReason {
+        QuorumNotReached,
+        Tie,
+        AbstainMajority,
+        /// Closed early because the outstanding weight could not reach quorum
+        QuorumUnreachable,
+        /// Option 0 did not exceed the proposal's approval fraction
+        ApprovalThresholdNotMet,
+        /// Every vote cast was an abstention
+        NoOptionVotes,
+    }
+
+    /// Treasury payout attached to a treasury proposal
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, scale_info::TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct TreasuryAction {
+        pub recipient: H160,
+        pub amount: Balance,
+    }
+
+    /// Contract call made when a proposal is executed
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, scale_info::TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ExecutionCall {
+        pub target: H160,
+        pub selector: [u8; 4],
+        /// SCALE-encoded arguments following the selector
+        pub input: Vec<u8>,
+        /// `ref_time` limit for the call
+        pub gas_limit: u64,
+    }
+
+    /// Pre-encoded call arguments, written to the call input as-is
+    struct RawInput<'a>(&'a [u8]);
+
+    impl scale::Encode for RawInput<'_> {
+        fn size_hint(&self) -> usize {
+            self.0.len()
+        }
+
+        fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+            dest.write(self.0);
+        }
+    }
+
     /// Proposal Status
     #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, scale_info::TypeInfo)]
     #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
@@ -118,6 +273,8 @@ mod treasury_governance {
         Rejected,
         Executed,
         Expired,
+        Cancelled,
+        Vetoed,
     }
 
     /// Main Proposal Structure
@@ -127,6 +284,8 @@ mod treasury_governance {
         pub id: u32,
         pub title: String,
         pub description: String,
+        /// Hash of the full proposal document kept off-chain, e.g. on IPFS
+        pub metadata_hash: Option<[u8; 32]>,
         pub proposal_type: ProposalType,
         pub governance_params: GovernanceParameters,
         pub voting_options: VotingOptions,
@@ -137,6 +296,28 @@ mod treasury_governance {
         pub status: ProposalStatus,
         pub vote_counts: Vec<u128>,
         pub total_voters: u32,
+        /// Registered voters at creation (quorum snapshot)
+        pub snapshot_voters: u32,
+        /// Blacklisted voters at creation, excluded from the quorum snapshot
+        pub snapshot_blacklisted: u32,
+        /// Voting power of eligible voters at creation (quorum denominator)
+        pub snapshot_total_power: u128,
+        /// Payout executed if a treasury proposal passes
+        pub treasury_action: Option<TreasuryAction>,
+        /// Contract call made on execution
+        pub execution_call: Option<ExecutionCall>,
+        /// Proposal that must be executed before this one can be
+        pub depends_on: Option<u32>,
+        /// Weight of abstaining votes
+        pub abstain_count: u128,
+        /// Weight of distinct voters that participated, abstentions included
+        pub participating_weight: u128,
+        /// Set when the proposal is rejected
+        pub rejection_reason: Option<RejectionReason>,
+        /// Account that executed the proposal
+        pub executed_by: Option<H160>,
+        /// Block at which the proposal was executed
+        pub executed_at: Option<u32>,
     }
 
     /// Vote Record
@@ -146,7 +327,31 @@ mod treasury_governance {
         pub voter: H160,
         pub choice: VoteChoice,
         pub timestamp: u32,
+        /// Weight added to the tally, conviction multiplier included
+        pub weight: u128,
+        /// Weight counted toward quorum and participation, before any
+        /// conviction multiplier
+        pub base_weight: u128,
+        /// All options approved on an approval ballot, or the preference
+        /// order on a ranked-choice ballot (empty otherwise)
+        pub selections: Vec<u32>,
+        /// Block until which the vote cannot be changed or revoked (its cast
+        /// block without a conviction lock)
+        pub lock_until: u32,
+        /// Delegators whose weight the vote carries, released on revoke
+        pub carried: Vec<H160>,
+        /// Credits spent on a quadratic ballot, refunded on revoke (0 otherwise)
+        pub credits_spent: u128,
+    }
+
+    /// Confirmation of a cast vote with the running tally of the chosen option
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, scale_info::TypeInfo)]
+    pub struct VoteReceipt {
+        pub proposal_id: u32,
+        pub option_index: u32,
         pub weight: u128,
+        pub new_total_for_option: u128,
+        pub timestamp: u32,
     }
 
     /// Contract Statistics
@@ -156,10 +361,16 @@ mod treasury_governance {
         pub active_proposals: u32,
         pub executed_proposals: u32,
         pub total_voters: u32,
+        /// Vote weight cast across all proposals, abstentions included
+        pub total_votes_cast: u128,
+        /// Share of eligible voting power that voted, in basis points,
+        /// averaged over resolved proposals
+        pub average_participation_bps: u32,
     }
 
     /// Proposal Results
     #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, scale_info::TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
     pub struct ProposalResults {
         pub proposal_id: u32,
         pub vote_counts: Vec<u128>,
@@ -183,10 +394,117 @@ mod treasury_governance {
         InvalidOptionIndex,
         QuorumNotReached,
         ExecutionDelayNotMet,
+        ExecutionWindowClosed,
+        VoterNotRegistered,
+        VoterBlacklisted,
+        ProposalNotPassed,
+        VetoWindowClosed,
+        RecipientNotApproved,
+        WeightChangeTooLarge,
+        AmendmentNotAllowed,
+        AmendmentWindowClosed,
+        NoExistingVote,
+        InvalidVotingMode,
+        DepositWeightingDisabled,
+        NoDeposit,
+        ActiveVotesExist,
+        TransferFailed,
+        InsufficientDeposit,
+        DepositNotRefundable,
+        BelowProposalThreshold,
+        ProposalLimitReached,
+        ContractPaused,
+        InvalidDelegation,
+        NotDelegated,
+        VoteDelegated,
+        ReserveViolation,
+        InvalidVotingPeriod,
+        BatchTooLarge,
+        CooldownActive,
+        HookFailed,
+        DuplicateVotingOption,
+        QuorumBelowMinimum,
+        ExtensionTooLarge,
+        ProposalTooLarge,
+        NotEligibleForProposal,
+        ExecutionCallFailed,
+        InsufficientCredits,
+        VoteLocked,
+        NotNextInQueue,
+        DependencyNotExecuted,
+        ProposerCannotVote,
+        ReentrancyDetected,
+        InvalidExecutionDelay,
+        AlreadyRegistered,
+        InsufficientElectorate,
+        AmendmentIncreasesAmount,
+        QueueHeadExecutable,
+        ConvictionLockTooLong,
     }
 
     pub type Result<T> = core::result::Result<T, Error>;
 
+    /// A change of leading option, as (block, new_leader_index)
+    pub type LeadChange = (u32, u32);
+
+    /// Emitted when an account registers as a voter
+    #[ink(event)]
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct VoterRegistered {
+        #[ink(topic)]
+        pub voter: H160,
+    }
+
+    /// Emitted when a proposal is created
+    #[ink(event)]
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct ProposalCreated {
+        pub id: u32,
+        #[ink(topic)]
+        pub proposer: H160,
+        pub proposal_type: ProposalType,
+    }
+
+    /// Emitted when a vote is cast
+    #[ink(event)]
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct VoteCast {
+        pub proposal_id: u32,
+        #[ink(topic)]
+        pub voter: H160,
+        pub option_index: u32,
+        pub weight: u128,
+    }
+
+    /// Emitted when a passed proposal is executed
+    #[ink(event)]
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct ProposalExecuted {
+        #[ink(topic)]
+        pub id: u32,
+        #[ink(topic)]
+        pub executor: H160,
+        pub block: u32,
+    }
+
+    /// Emitted when a proposal's voting period is extended
+    #[ink(event)]
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct VotingPeriodExtended {
+        #[ink(topic)]
+        pub proposal_id: u32,
+        pub new_voting_end: u32,
+    }
+
+    /// Emitted when ownership passes to a new account
+    #[ink(event)]
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        pub previous_owner: H160,
+        #[ink(topic)]
+        pub new_owner: H160,
+    }
 
 
     /// Main Contract Storage
@@ -200,12 +518,166 @@ mod treasury_governance {
         votes: Mapping<(u32, H160), Vote>,
         /// List of all proposal IDs
         proposal_ids: Vec<u32>,
+        /// Passed proposals awaiting execution, in the order they passed
+        execution_queue: Vec<u32>,
+        /// Set while `execute_proposal` runs. Kept in its own storage cell so
+        /// the flag is visible to calls made back into the contract.
+        executing: Lazy<bool>,
         /// Total number of registered voters (for quorum calculation)
         total_voters: u32,
         /// Contract owner
         owner: H160,
         /// Registered voters
         registered_voters: Mapping<H160, bool>,
+        /// Blacklisted voters (excluded from voting and quorum)
+        blacklisted: Mapping<H160, bool>,
+        /// Number of registered voters currently blacklisted
+        total_blacklisted: u32,
+        /// Sum of the voting weights of registered, non-blacklisted voters
+        total_voting_power: u128,
+        /// Attempt early resolution after every vote
+        resolve_on_vote: bool,
+        /// Explicit voting weights (registered voters default to 1)
+        voting_power: Mapping<H160, u128>,
+        /// Cancel a voter's active proposals when they deregister
+        cancel_proposals_on_deregister: bool,
+        /// First proposal ID each voter is eligible for (created after registration)
+        first_eligible_proposal: Mapping<H160, u32>,
+        /// Votes cast by each voter on proposals they were eligible for
+        eligible_votes_cast: Mapping<H160, u32>,
+        /// Per-type voting weights, preferred over the general weight
+        type_weights: Mapping<(H160, ProposalType), u128>,
+        /// Account allowed to veto passed proposals during the execution delay
+        guardian: Option<H160>,
+        /// Vetoes issued, as position -> (proposal_id, guardian)
+        veto_log: Mapping<u32, (u32, H160)>,
+        /// Number of entries in `veto_log`
+        veto_count: u32,
+        /// Block of the last proposal creation, vote or execution
+        last_activity_block: u32,
+        /// Recipients treasury proposals may pay out to when restricted
+        approved_recipients: Mapping<H160, bool>,
+        /// Only allow treasury payouts to approved recipients
+        restrict_recipients: bool,
+        /// Let proposers vote on their own proposals
+        allow_proposer_vote: bool,
+        /// Limit how far a voter's weight may move in one update
+        smooth_weight_changes: bool,
+        /// Maximum weight change per update when smoothing is enabled
+        max_weight_delta: u128,
+        /// Reject proposals where abstentions outweigh actual votes
+        abstain_majority_rejects: bool,
+        /// Deposit required to create a proposal
+        proposal_deposit: Balance,
+        /// Per-type deposits, preferred over the global deposit
+        type_deposits: Mapping<ProposalType, Balance>,
+        /// Let voters lock funds to set their weight
+        deposit_weighting: bool,
+        /// Funds each voter has locked for weight
+        locked_deposits: Mapping<H160, Balance>,
+        /// Account nominated to become owner, pending acceptance
+        pending_owner: Option<H160>,
+        /// Which events are emitted
+        event_level: EventLevel,
+        /// Require at least one vote for quorum whenever anyone can vote
+        min_quorum_one: bool,
+        /// Deposits held per proposal, as (depositor, amount)
+        proposal_deposits: Mapping<u32, (H160, Balance)>,
+        /// Sum of `locked_deposits`, owed back to voters
+        total_locked: Balance,
+        /// Sum of refundable `proposal_deposits`, owed back to proposers
+        total_held_deposits: Balance,
+        /// Roles granted to accounts; the owner implicitly holds every role
+        roles: Mapping<(H160, Role), bool>,
+        /// Only allow executors to execute proposals (on by default)
+        restrict_execution: bool,
+        /// Refund deposits when the proposer cancels before any votes
+        refund_clean_cancellations: bool,
+        /// Voting weight required to create proposals
+        proposal_threshold: u128,
+        /// Lifetime cap on proposals per proposer (0 means unlimited)
+        max_proposals_per_proposer: u32,
+        /// Proposals created by each account
+        proposals_created: Mapping<H160, u32>,
+        /// Emergency stop for proposal creation, voting and execution
+        paused: bool,
+        /// Record lead changes for every proposal
+        track_lead_changes: bool,
+        /// Lead changes per proposal, as (block, new_leader_index)
+        lead_changes: Mapping<u32, Vec<LeadChange>>,
+        /// Account each voter delegated their weight to
+        delegations: Mapping<H160, H160>,
+        /// Accounts that delegated directly to each delegate
+        delegators: Mapping<H160, Vec<H160>>,
+        /// How many delegation hops weight flows through
+        max_delegation_depth: u32,
+        /// Delegators whose weight a delegate cast, as (proposal_id, delegator) -> delegate
+        carried_votes: Mapping<(u32, H160), H160>,
+        /// Balance the treasury must keep after a payout
+        treasury_reserve: Balance,
+        /// Block at which each current voter registered
+        registered_at: Mapping<H160, u32>,
+        /// Number of current voters who registered at each block
+        registration_counts: Mapping<u32, u32>,
+        /// Blocks at which voters registered, as position -> block, ascending
+        /// and without repeats
+        registration_blocks: Mapping<u32, u32>,
+        /// Number of entries in `registration_blocks`
+        registration_block_count: u32,
+        /// Leave the proposer's own vote out of quorum
+        exclude_proposer_from_quorum: bool,
+        /// Blocks a voter must wait after deregistering before registering again
+        reregister_cooldown_blocks: u32,
+        /// Block at which each account last deregistered
+        deregistered_at: Mapping<H160, u32>,
+        /// Contract notified after each successful execution
+        execution_hook: Option<H160>,
+        /// Revert the execution when the hook call fails
+        require_hook_success: bool,
+        /// Block at which the contract was instantiated
+        deployed_at_block: u32,
+        /// Scale down the weight of recently registered voters
+        maturity_weighting_enabled: bool,
+        /// Blocks after registration until a voter carries full weight
+        maturity_blocks: u32,
+        /// Accounts that voted on each proposal, in voting order
+        proposal_voters: Mapping<u32, Vec<H160>>,
+        /// Proposals each account voted on, in voting order
+        voter_history: Mapping<H160, Vec<u32>>,
+        /// Results frozen when `update_proposal_status` finalizes a proposal
+        cached_results: Mapping<u32, ProposalResults>,
+        /// Most voting options a proposal may offer
+        max_voting_options: u32,
+        /// Shortest voting period a proposal may use, in blocks (0 = no floor)
+        min_voting_period_blocks: u32,
+        /// Fewest eligible (registered, not blacklisted) voters needed to
+        /// create a proposal (0 = no floor)
+        min_electorate: u32,
+        /// Parameters used by `create_proposal_with_defaults`
+        default_governance_params: GovernanceParameters,
+        /// Weakest quorum threshold allowed per proposal type
+        type_min_quorums: Mapping<ProposalType, QuorumThreshold>,
+        /// Blocks each proposal's voting period has been extended by
+        voting_extensions: Mapping<u32, u32>,
+        /// Accounts allowed to vote on restricted proposals
+        proposal_allowlists: Mapping<u32, Vec<H160>>,
+        /// Membership index over `proposal_allowlists`
+        eligible_members: Mapping<(u32, H160), ()>,
+        /// Quorum thresholds that replace the proposal's own, per type
+        type_quorum_overrides: Mapping<ProposalType, QuorumThreshold>,
+        /// Credits each voter can spend on quadratic proposals
+        credits: Mapping<H160, u128>,
+        /// Proposals that depend on each proposal, by dependency
+        dependents: Mapping<u32, Vec<u32>>,
+        /// Number of proposals created, per type
+        type_proposal_counts: Mapping<ProposalType, u32>,
+        /// Number of proposals currently in each status, per type
+        status_counts: Mapping<(ProposalType, ProposalStatus), u32>,
+        /// Number of voters with a weight set in `type_weights`, per type
+        type_weighted_voters: Mapping<ProposalType, u32>,
+        /// Proposals each voter cast a conviction vote on, pruned of ended
+        /// locks whenever one is added
+        conviction_locks: Mapping<H160, Vec<u32>>,
     }
 
     impl TreasuryGovernance {
@@ -217,393 +689,3493 @@ mod treasury_governance {
                 proposals: Mapping::new(),
                 votes: Mapping::new(),
                 proposal_ids: Vec::new(),
+                execution_queue: Vec::new(),
+                executing: Lazy::new(),
                 total_voters: 0,
                 owner: Self::env().caller(),
                 registered_voters: Mapping::new(),
+                blacklisted: Mapping::new(),
+                total_blacklisted: 0,
+                total_voting_power: 0,
+                resolve_on_vote: false,
+                voting_power: Mapping::new(),
+                cancel_proposals_on_deregister: false,
+                first_eligible_proposal: Mapping::new(),
+                eligible_votes_cast: Mapping::new(),
+                type_weights: Mapping::new(),
+                guardian: None,
+                veto_log: Mapping::new(),
+                veto_count: 0,
+                last_activity_block: 0,
+                approved_recipients: Mapping::new(),
+                restrict_recipients: false,
+                allow_proposer_vote: true,
+                smooth_weight_changes: false,
+                max_weight_delta: 0,
+                abstain_majority_rejects: false,
+                proposal_deposit: 0,
+                type_deposits: Mapping::new(),
+                deposit_weighting: false,
+                locked_deposits: Mapping::new(),
+                pending_owner: None,
+                event_level: EventLevel::All,
+                min_quorum_one: false,
+                proposal_deposits: Mapping::new(),
+                total_locked: 0,
+                total_held_deposits: 0,
+                roles: Mapping::new(),
+                restrict_execution: true,
+                refund_clean_cancellations: false,
+                proposal_threshold: 0,
+                max_proposals_per_proposer: 0,
+                proposals_created: Mapping::new(),
+                paused: false,
+                track_lead_changes: false,
+                lead_changes: Mapping::new(),
+                delegations: Mapping::new(),
+                delegators: Mapping::new(),
+                max_delegation_depth: 1,
+                carried_votes: Mapping::new(),
+                treasury_reserve: 0,
+                registered_at: Mapping::new(),
+                registration_counts: Mapping::new(),
+                registration_blocks: Mapping::new(),
+                registration_block_count: 0,
+                exclude_proposer_from_quorum: false,
+                reregister_cooldown_blocks: 0,
+                deregistered_at: Mapping::new(),
+                execution_hook: None,
+                require_hook_success: false,
+                deployed_at_block: Self::env().block_number(),
+                maturity_weighting_enabled: false,
+                maturity_blocks: 0,
+                proposal_voters: Mapping::new(),
+                voter_history: Mapping::new(),
+                cached_results: Mapping::new(),
+                max_voting_options: 10,
+                min_voting_period_blocks: 0,
+                min_electorate: 0,
+                default_governance_params: GovernanceParameters {
+                    voting_period: VotingPeriod::SevenDays,
+                    quorum_threshold: QuorumThreshold::Ten,
+                    execution_delay: ExecutionDelay::OneDay,
+                    execution_window_blocks: 0,
+                    min_absolute_weight: 0,
+                    allow_payload_amendment: false,
+                    voting_mode: VotingMode::Linear,
+                    min_approval_bps: 0,
+                },
+                type_min_quorums: Mapping::new(),
+                voting_extensions: Mapping::new(),
+                proposal_allowlists: Mapping::new(),
+                eligible_members: Mapping::new(),
+                type_quorum_overrides: Mapping::new(),
+                credits: Mapping::new(),
+                dependents: Mapping::new(),
+                type_proposal_counts: Mapping::new(),
+                status_counts: Mapping::new(),
+                type_weighted_voters: Mapping::new(),
+                conviction_locks: Mapping::new(),
             }
         }
 
         /// Register as a voter
         #[ink(message)]
         pub fn register_voter(&mut self) -> Result<()> {
+            self.ensure_not_executing()?;
             let caller = self.env().caller();
             
             if self.registered_voters.get(caller).is_some() {
-                return Err(Error::AlreadyVoted); // Reusing error for already registered
+                return Err(Error::AlreadyRegistered);
             }
 
-            self.registered_voters.insert(caller, &true);
-            self.total_voters = self.total_voters.saturating_add(1);
+            // Hold off voters who just left
+            if let Some(left_at) = self.deregistered_at.get(caller) {
+                if self.env().block_number() < left_at.saturating_add(self.reregister_cooldown_blocks) {
+                    return Err(Error::CooldownActive);
+                }
+            }
 
-            // self.env().emit_event(VoterRegistered { voter: caller });
+            self.add_voter(caller);
             Ok(())
         }
 
-        /// Create a new proposal
+        /// Register an account on its behalf (owner only)
         #[ink(message)]
-        pub fn create_proposal(
-            &mut self,
-            title: String,
-            description: String,
-            proposal_type: ProposalType,
-            governance_params: GovernanceParameters,
-            voting_options: VotingOptions,
-        ) -> Result<u32> {
-            // Validate voting options
-            if voting_options.options.is_empty() || voting_options.options.len() > 10 {
-                return Err(Error::InvalidVotingOptions);
-            }
-
-            let current_block = self.env().block_number();
-            let voting_period_blocks = governance_params.voting_period.to_blocks();
-            let execution_delay_blocks = governance_params.execution_delay.to_blocks();
+        pub fn register_voter_for(&mut self, account: H160) -> Result<()> {
+            self.ensure_owner()?;
 
-            // Calculate times with overflow protection
-            let voting_end = current_block.saturating_add(voting_period_blocks);
-            let execution_time = voting_end.saturating_add(execution_delay_blocks);
-
-            // Initialize vote counts
-            let mut vote_counts = Vec::new();
-            for _ in 0..voting_options.options.len() {
-                vote_counts.push(0u128);
+            if self.registered_voters.get(account).is_some() {
+                return Err(Error::AlreadyRegistered);
             }
 
-            let proposal = Proposal {
-                id: self.next_proposal_id,
-                title: title.clone(),
-                description,
-                proposal_type,
-                governance_params,
-                voting_options: voting_options.clone(),
-                proposer: self.env().caller(),
-                created_at: current_block,
-                voting_end,
-                execution_time,
-                status: ProposalStatus::Active,
-                vote_counts,
-                total_voters: 0,
-            };
-
-            // Store proposal
-            self.proposals.insert(self.next_proposal_id, &proposal);
-            self.proposal_ids.push(self.next_proposal_id);
-
-            let proposal_id = self.next_proposal_id;
-            self.next_proposal_id = self.next_proposal_id.saturating_add(1);
+            self.add_voter(account);
+            Ok(())
+        }
 
-            
+        /// Register several accounts at once (owner only), skipping those
+        /// already registered. Returns how many were added. At most 100.
+        #[ink(message)]
+        pub fn register_voters(&mut self, voters: Vec<H160>) -> Result<u32> {
+            self.ensure_owner()?;
+            if voters.len() > 100 {
+                return Err(Error::BatchTooLarge);
+            }
 
-            Ok(proposal_id)
+            let mut added: u32 = 0;
+            for voter in voters {
+                if self.registered_voters.get(voter).is_some() {
+                    continue;
+                }
+                self.add_voter(voter);
+                added = added.saturating_add(1);
+            }
+            Ok(added)
         }
 
-        /// Vote on a proposal
+        /// Leave the voter set. Votes already cast keep counting.
         #[ink(message)]
-        pub fn vote(&mut self, proposal_id: u32, option_index: u32) -> Result<()> {
+        pub fn deregister_voter(&mut self) -> Result<()> {
+            self.ensure_not_executing()?;
             let caller = self.env().caller();
-            let current_block = self.env().block_number();
 
-            // Check if voter is registered
             if self.registered_voters.get(caller).is_none() {
                 return Err(Error::NotAuthorized);
             }
+            self.ensure_not_conviction_locked(caller)?;
 
-            // Get proposal
-            let mut proposal = self.proposals.get(proposal_id)
-                .ok_or(Error::ProposalNotFound)?;
-
-            // Check if proposal is active
-            if proposal.status != ProposalStatus::Active {
-                return Err(Error::ProposalNotActive);
-            }
+            self.total_voting_power = self.total_voting_power.saturating_sub(self.counted_power(caller));
+            self.registered_voters.remove(caller);
+            self.total_voters = self.total_voters.saturating_sub(1);
+            self.first_eligible_proposal.remove(caller);
+            self.eligible_votes_cast.remove(caller);
+            self.deregistered_at.insert(caller, &self.env().block_number());
 
-            // Check if voting period has ended
-            if current_block > proposal.voting_end {
-                return Err(Error::VotingPeriodEnded);
+            if let Some(block) = self.registered_at.take(caller) {
+                let count = self.registration_counts.get(block).unwrap_or(0);
+                self.registration_counts.insert(block, &count.saturating_sub(1));
             }
 
-            // Check if user has already voted
-            if self.votes.get((proposal_id, caller)).is_some() {
-                return Err(Error::AlreadyVoted);
+            if self.blacklisted.get(caller).is_some() {
+                self.blacklisted.remove(caller);
+                self.total_blacklisted = self.total_blacklisted.saturating_sub(1);
             }
 
-            // Validate option index
-            if option_index as usize >= proposal.voting_options.options.len() {
-                return Err(Error::InvalidOptionIndex);
+            // Optionally withdraw the leaver's active proposals
+            if self.cancel_proposals_on_deregister {
+                for position in 0..self.proposal_ids.len() {
+                    let proposal_id = self.proposal_ids[position];
+                    if let Some(mut proposal) = self.proposals.get(proposal_id) {
+                        if proposal.proposer == caller && proposal.status == ProposalStatus::Active {
+                            proposal.status = ProposalStatus::Cancelled;
+                            self.proposals.insert(proposal_id, &proposal);
+                            self.record_status_change(&proposal, ProposalStatus::Active);
+                            self.forfeit_deposit(proposal_id);
+                            self.settle_dependents(proposal_id);
+                        }
+                    }
+                }
             }
 
-            // Create vote record
-            let vote = Vote {
-                voter: caller,
-                choice: VoteChoice {
-                    option_index,
-                    option_text: proposal.voting_options.options[option_index as usize].clone(),
-                },
-                timestamp: current_block,
-                weight: 1, // Simple 1:1 voting weight
-            };
+            Ok(())
+        }
 
-            // Store vote
-            self.votes.insert((proposal_id, caller), &vote);
+        /// Lock the transferred funds and use the caller's total locked
+        /// amount as their voting weight. With weight smoothing enabled the
+        /// weight may move by at most `max_weight_delta` per lock.
+        #[ink(message, payable)]
+        pub fn lock_for_weight(&mut self) -> Result<()> {
+            self.ensure_not_executing()?;
+            let caller = self.env().caller();
 
-            // Update vote counts with overflow protection
-            let option_idx = option_index as usize;
-            if option_idx < proposal.vote_counts.len() {
-                proposal.vote_counts[option_idx] = proposal.vote_counts[option_idx].saturating_add(1);
+            if !self.deposit_weighting {
+                return Err(Error::DepositWeightingDisabled);
+            }
+            if self.registered_voters.get(caller).is_none() {
+                return Err(Error::VoterNotRegistered);
             }
-            proposal.total_voters = proposal.total_voters.saturating_add(1);
-
-            // Update proposal
-            self.proposals.insert(proposal_id, &proposal);
 
-    
+            let amount: Balance = self.env().transferred_value().as_u128();
+            if amount == 0 {
+                return Err(Error::NoDeposit);
+            }
 
+            // The new weight is subject to the same limits as any other update
+            let locked = self.locked_deposits.get(caller).unwrap_or(0).saturating_add(amount);
+            self.apply_voting_power(caller, locked)?;
+            self.locked_deposits.insert(caller, &locked);
+            self.total_locked = self.total_locked.saturating_add(amount);
             Ok(())
         }
 
-        /// Update proposal status (can be called by anyone)
+        /// Return the caller's locked funds and zero their weight. Not
+        /// allowed while they have a vote, direct or delegated, on an active proposal.
         #[ink(message)]
-        pub fn update_proposal_status(&mut self, proposal_id: u32) -> Result<()> {
-            let current_block = self.env().block_number();
-            let mut proposal = self.proposals.get(proposal_id)
-                .ok_or(Error::ProposalNotFound)?;
+        pub fn unlock_weight(&mut self) -> Result<()> {
+            self.ensure_not_executing()?;
+            let caller = self.env().caller();
 
-            // Only update if proposal is still active
-            if proposal.status != ProposalStatus::Active {
-                return Ok(());
+            let locked = self.locked_deposits.get(caller).unwrap_or(0);
+            if locked == 0 {
+                return Err(Error::NoDeposit);
             }
+            self.ensure_not_conviction_locked(caller)?;
 
-            // Check if voting period has ended
-            if current_block <= proposal.voting_end {
-                return Ok(());
+            let has_active_vote = self.proposal_ids.iter().any(|&proposal_id| {
+                (self.votes.contains((proposal_id, caller))
+                    || self.carried_votes.contains((proposal_id, caller)))
+                    && self.proposals
+                        .get(proposal_id)
+                        .is_some_and(|p| p.status == ProposalStatus::Active)
+            });
+            if has_active_vote {
+                return Err(Error::ActiveVotesExist);
             }
 
-            // Calculate quorum with overflow protection
-            let quorum_percentage = proposal.governance_params.quorum_threshold.to_percentage();
-            let quorum_required = (self.total_voters as u128)
-                .saturating_mul(quorum_percentage as u128)
-                .saturating_div(100);
-            let total_votes: u128 = proposal.vote_counts.iter().sum();
+            self.locked_deposits.remove(caller);
+            self.total_locked = self.total_locked.saturating_sub(locked);
+            let before = self.counted_power(caller);
+            self.voting_power.insert(caller, &0);
+            self.sync_total_power(caller, before);
 
-            // Check if quorum is reached
-            if total_votes < quorum_required {
-                proposal.status = ProposalStatus::Rejected;
-                self.proposals.insert(proposal_id, &proposal);
-                return Ok(());
-            }
+            self.env()
+                .transfer(caller, U256::from(locked))
+                .map_err(|_| Error::TransferFailed)?;
+            Ok(())
+        }
+
+        /// Get the funds an account has locked for weight
+        #[ink(message)]
+        pub fn get_locked_deposit(&self, voter: H160) -> Balance {
+            self.locked_deposits.get(voter).unwrap_or(0)
+        }
 
-            // Find winning option
-            let mut max_votes = 0u128;
-            let mut _winning_index = 0u32;
-            let mut tie = false;
+        /// Delegate the caller's weight to another account, replacing any
+        /// previous delegation. Weight flows transitively through chains up to
+        /// `max_delegation_depth` hops; delegations that would form a cycle are
+        /// rejected. While delegated the caller cannot vote, except with their
+        /// own credits on quadratic proposals, and a delegator's weight
+        /// counts once per proposal: whoever votes first uses it.
+        #[ink(message)]
+        pub fn delegate(&mut self, to: H160) -> Result<()> {
+            self.ensure_not_executing()?;
+            let caller = self.env().caller();
 
-            for (index, &votes) in proposal.vote_counts.iter().enumerate() {
-                if votes > max_votes {
-                    max_votes = votes;
-                    _winning_index = u32::try_from(index).unwrap_or(0);
-                    tie = false;
-                } else if votes == max_votes && votes > 0 {
-                    tie = true;
-                }
+            if self.registered_voters.get(caller).is_none() {
+                return Err(Error::VoterNotRegistered);
+            }
+            if to == caller {
+                return Err(Error::InvalidDelegation);
             }
+            self.ensure_not_conviction_locked(caller)?;
 
-            // Handle ties
-            if tie {
-                proposal.status = ProposalStatus::Rejected;
-            } else {
-                proposal.status = ProposalStatus::Passed;
+            // Walk the delegate's chain; it must not lead back to the caller
+            let mut next = self.delegations.get(to);
+            while let Some(account) = next {
+                if account == caller {
+                    return Err(Error::InvalidDelegation);
+                }
+                next = self.delegations.get(account);
             }
 
-            self.proposals.insert(proposal_id, &proposal);
+            self.remove_delegation(caller);
+            self.delegations.insert(caller, &to);
+            let mut delegators = self.delegators.get(to).unwrap_or_default();
+            delegators.push(caller);
+            self.delegators.insert(to, &delegators);
             Ok(())
         }
 
-        /// Execute a passed proposal
+        /// Take back the caller's delegated weight
         #[ink(message)]
-        pub fn execute_proposal(&mut self, proposal_id: u32) -> Result<()> {
-            let current_block = self.env().block_number();
-            let mut proposal = self.proposals.get(proposal_id)
-                .ok_or(Error::ProposalNotFound)?;
-
-            // Check if proposal is passed
-            if proposal.status != ProposalStatus::Passed {
-                return Err(Error::ProposalNotReadyForExecution);
-            }
-
-            // Check if execution delay has passed
-            if current_block < proposal.execution_time {
-                return Err(Error::ExecutionDelayNotMet);
+        pub fn undelegate(&mut self) -> Result<()> {
+            self.ensure_not_executing()?;
+            let caller = self.env().caller();
+            if !self.delegations.contains(caller) {
+                return Err(Error::NotDelegated);
             }
-
-            // Update status to executed
-            proposal.status = ProposalStatus::Executed;
-            self.proposals.insert(proposal_id, &proposal);
-
+            self.remove_delegation(caller);
             Ok(())
         }
 
-        /// Get a specific proposal
+        /// Get the account a voter delegated to, if any
         #[ink(message)]
-        pub fn get_proposal(&self, proposal_id: u32) -> Result<Proposal> {
-            self.proposals.get(proposal_id)
-                .ok_or(Error::ProposalNotFound)
+        pub fn get_delegate(&self, voter: H160) -> Option<H160> {
+            self.delegations.get(voter)
         }
 
-        /// Get all proposal IDs
+        /// Get the accounts that delegated directly to an account
         #[ink(message)]
-        pub fn get_all_proposal_ids(&self) -> Vec<u32> {
-            self.proposal_ids.clone()
+        pub fn get_delegators(&self, delegate: H160) -> Vec<H160> {
+            self.delegators.get(delegate).unwrap_or_default()
         }
 
-        /// Get user's vote on a proposal
+        /// Get the delegate that cast a delegator's weight on a proposal, if any
         #[ink(message)]
-        pub fn get_user_vote(&self, proposal_id: u32, user: H160) -> Result<Vote> {
-            self.votes.get((proposal_id, user))
-                .ok_or(Error::ProposalNotFound)
+        pub fn get_carried_by(&self, proposal_id: u32, delegator: H160) -> Option<H160> {
+            self.carried_votes.get((proposal_id, delegator))
         }
 
-        /// Get contract statistics
-        #[ink(message)]
-        pub fn get_stats(&self) -> ContractStats {
-            let mut active_proposals: u32 = 0;
-            let mut executed_proposals: u32 = 0;
+        /// Create a new proposal. The transferred value is held as the
+        /// proposal deposit and must cover `get_proposal_cost`.
+        #[ink(message, payable)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn create_proposal(
+            &mut self,
+            title: String,
+            description: String,
+            proposal_type: ProposalType,
+            governance_params: GovernanceParameters,
+            voting_options: VotingOptions,
+            treasury_action: Option<TreasuryAction>,
+            metadata_hash: Option<[u8; 32]>,
+            eligible_voters: Option<Vec<H160>>,
+            execution_call: Option<ExecutionCall>,
+            depends_on: Option<u32>,
+        ) -> Result<u32> {
+            self.ensure_not_executing()?;
+            self.ensure_not_paused()?;
 
-            for &proposal_id in &self.proposal_ids {
-                if let Some(proposal) = self.proposals.get(proposal_id) {
-                    match proposal.status {
-                        ProposalStatus::Active => active_proposals = active_proposals.saturating_add(1),
-                        ProposalStatus::Executed => executed_proposals = executed_proposals.saturating_add(1),
-                        _ => {}
-                    }
+            // Keep the on-chain text short; longer documents go in the metadata
+            if title.len() > MAX_TITLE_LENGTH || description.len() > MAX_DESCRIPTION_LENGTH {
+                return Err(Error::ProposalTooLarge);
+            }
+
+            // Validate voting options
+            if voting_options.options.is_empty()
+                || voting_options.options.len() > self.max_voting_options as usize
+            {
+                return Err(Error::InvalidVotingOptions);
+            }
+            for (position, option) in voting_options.options.iter().enumerate() {
+                if option.is_empty() || option.len() > MAX_OPTION_LENGTH {
+                    return Err(Error::InvalidVotingOptions);
+                }
+                if voting_options.options[..position].contains(option) {
+                    return Err(Error::DuplicateVotingOption);
                 }
             }
 
-            ContractStats {
-                total_proposals: u32::try_from(self.proposal_ids.len()).unwrap_or(0),
-                active_proposals,
-                executed_proposals,
-                total_voters: self.total_voters,
+            // Validate custom voting period bounds
+            if let VotingPeriod::Custom(blocks) = governance_params.voting_period {
+                if !(MIN_CUSTOM_VOTING_PERIOD..=MAX_CUSTOM_VOTING_PERIOD).contains(&blocks) {
+                    return Err(Error::InvalidVotingPeriod);
+                }
+            }
+            if governance_params.voting_period.to_blocks() < self.min_voting_period_blocks {
+                return Err(Error::InvalidVotingPeriod);
             }
-        }
 
-        /// Get total registered voters
+            // Keep custom timelocks within bounds
+            if let ExecutionDelay::Custom(blocks) = governance_params.execution_delay {
+                if blocks > MAX_CUSTOM_EXECUTION_DELAY {
+                    return Err(Error::InvalidExecutionDelay);
+                }
+            }
+
+            if governance_params.min_approval_bps > 10_000 {
+                return Err(Error::InvalidProposal);
+            }
+
+            // Validate custom quorum bounds
+            Self::ensure_valid_quorum(&governance_params.quorum_threshold)?;
+
+            // Keep the quorum at or above the type's minimum
+            if let Some(minimum) = self.type_min_quorums.get(proposal_type.clone()) {
+                if governance_params.quorum_threshold.to_percentage() < minimum.to_percentage() {
+                    return Err(Error::QuorumBelowMinimum);
+                }
+            }
+
+            // Validate the allowlist, dropping duplicates
+            let eligible_voters = match eligible_voters {
+                Some(list) => {
+                    if list.is_empty() {
+                        return Err(Error::InvalidProposal);
+                    }
+                    if list.len() > 100 {
+                        return Err(Error::BatchTooLarge);
+                    }
+                    let mut members: Vec<H160> = Vec::new();
+                    for voter in list {
+                        if !members.contains(&voter) {
+                            members.push(voter);
+                        }
+                    }
+                    Some(members)
+                }
+                None => None,
+            };
+
+            // Validate treasury payout
+            if let Some(action) = &treasury_action {
+                if proposal_type != ProposalType::Treasury {
+                    return Err(Error::InvalidProposal);
+                }
+                if self.restrict_recipients && self.approved_recipients.get(action.recipient).is_none() {
+                    return Err(Error::RecipientNotApproved);
+                }
+            }
+
+            // The dependency must already exist, so it was created earlier
+            // and can never sit behind this proposal in the queue, and it
+            // must still be able to execute
+            if let Some(dependency) = depends_on {
+                let dependency = self.proposals.get(dependency)
+                    .ok_or(Error::ProposalNotFound)?;
+                if !matches!(
+                    dependency.status,
+                    ProposalStatus::Active | ProposalStatus::Passed | ProposalStatus::Executed
+                ) {
+                    return Err(Error::DependencyNotExecuted);
+                }
+            }
+
+            // Refuse proposals an electorate this small could pass trivially
+            if self.total_voters.saturating_sub(self.total_blacklisted) < self.min_electorate {
+                return Err(Error::InsufficientElectorate);
+            }
+
+            // Require enough voting power to propose, unless the caller is a proposer
+            if !self.has_role(self.env().caller(), Role::Proposer)
+                && self.get_voter_weight(self.env().caller()) < self.proposal_threshold
+            {
+                return Err(Error::BelowProposalThreshold);
+            }
+
+            // Enforce the per-proposer lifetime cap
+            let created = self.proposals_created.get(self.env().caller()).unwrap_or(0);
+            if self.max_proposals_per_proposer > 0 && created >= self.max_proposals_per_proposer {
+                return Err(Error::ProposalLimitReached);
+            }
+
+            // Require the configured deposit
+            let deposit: Balance = self.env().transferred_value().as_u128();
+            if deposit < self.get_proposal_cost(proposal_type.clone()) {
+                return Err(Error::InsufficientDeposit);
+            }
+
+            let current_block = self.env().block_number();
+            let voting_period_blocks = governance_params.voting_period.to_blocks();
+            let execution_delay_blocks = governance_params.execution_delay.to_blocks();
+
+            // Calculate times with overflow protection
+            let voting_end = current_block.saturating_add(voting_period_blocks);
+            let execution_time = voting_end.saturating_add(execution_delay_blocks);
+
+            // Initialize vote counts
+            let vote_counts = ink::prelude::vec![0u128; voting_options.options.len()];
+
+            // Quorum is measured against the allowlist when there is one
+            let (snapshot_voters, snapshot_blacklisted, snapshot_total_power) = match &eligible_voters {
+                Some(members) => {
+                    let registered = members
+                        .iter()
+                        .filter(|&&voter| self.registered_voters.get(voter).is_some());
+                    let voters = registered.clone().count() as u32;
+                    let blacklisted = registered
+                        .clone()
+                        .filter(|&&voter| self.blacklisted.get(voter).is_some())
+                        .count() as u32;
+                    let power = members
+                        .iter()
+                        .fold(0u128, |total, &voter| total.saturating_add(self.counted_power(voter)));
+                    (voters, blacklisted, power)
+                }
+                None => (self.total_voters, self.total_blacklisted, self.total_voting_power),
+            };
+
+            let proposal = Proposal {
+                id: self.next_proposal_id,
+                title: title.clone(),
+                description,
+                metadata_hash,
+                proposal_type,
+                governance_params,
+                voting_options: voting_options.clone(),
+                proposer: self.env().caller(),
+                created_at: current_block,
+                voting_end,
+                execution_time,
+                status: ProposalStatus::Active,
+                vote_counts,
+                total_voters: 0,
+                snapshot_voters,
+                snapshot_blacklisted,
+                snapshot_total_power,
+                treasury_action,
+                execution_call,
+                depends_on,
+                abstain_count: 0,
+                participating_weight: 0,
+                rejection_reason: None,
+                executed_by: None,
+                executed_at: None,
+            };
+
+            // Store proposal
+            self.proposals.insert(self.next_proposal_id, &proposal);
+            self.proposal_ids.push(self.next_proposal_id);
+
+            let proposal_id = self.next_proposal_id;
+            self.next_proposal_id = self.next_proposal_id.saturating_add(1);
+            if deposit > 0 {
+                self.proposal_deposits.insert(proposal_id, &(proposal.proposer, deposit));
+                self.total_held_deposits = self.total_held_deposits.saturating_add(deposit);
+            }
+            let of_type = self.type_proposal_counts.get(&proposal.proposal_type).unwrap_or(0);
+            self.type_proposal_counts.insert(&proposal.proposal_type, &of_type.saturating_add(1));
+            let active_key = (proposal.proposal_type.clone(), ProposalStatus::Active);
+            let active = self.status_counts.get(&active_key).unwrap_or(0);
+            self.status_counts.insert(&active_key, &active.saturating_add(1));
+            if let Some(dependency) = depends_on {
+                let mut dependents = self.dependents.get(dependency).unwrap_or_default();
+                dependents.push(proposal_id);
+                self.dependents.insert(dependency, &dependents);
+            }
+            if let Some(members) = eligible_voters {
+                for &voter in &members {
+                    self.eligible_members.insert((proposal_id, voter), &());
+                }
+                self.proposal_allowlists.insert(proposal_id, &members);
+            }
+            self.proposals_created.insert(proposal.proposer, &created.saturating_add(1));
+            self.last_activity_block = current_block;
+
+            if self.emits(EventLevel::Critical) {
+                self.env().emit_event(ProposalCreated {
+                    id: proposal_id,
+                    proposer: proposal.proposer,
+                    proposal_type: proposal.proposal_type,
+                });
+            }
+
+            Ok(proposal_id)
+        }
+
+        /// Create a proposal using the owner-configured default parameters
+        #[ink(message, payable)]
+        pub fn create_proposal_with_defaults(
+            &mut self,
+            title: String,
+            description: String,
+            proposal_type: ProposalType,
+            voting_options: VotingOptions,
+            metadata_hash: Option<[u8; 32]>,
+        ) -> Result<u32> {
+            let governance_params = self.default_governance_params.clone();
+            self.create_proposal(title, description, proposal_type, governance_params, voting_options, None, metadata_hash, None, None, None)
+        }
+
+        /// Vote on a proposal
         #[ink(message)]
-        pub fn get_total_voters(&self) -> u32 {
-            self.total_voters
+        pub fn vote(&mut self, proposal_id: u32, option_index: u32) -> Result<()> {
+            self.cast_vote(proposal_id, ink::prelude::vec![option_index], None, 0)
         }
 
-        /// Check if proposal has reached quorum
+        /// Check whether `voter` could vote for an option without changing
+        /// state. Returns the weight the vote would record, or the error
+        /// `vote` would fail with.
         #[ink(message)]
-        pub fn has_reached_quorum(&self, proposal_id: u32) -> Result<bool> {
+        pub fn dry_run_vote(&self, proposal_id: u32, option_index: u32, voter: H160) -> Result<u128> {
+            let proposal = self.validate_vote(voter, proposal_id, &[option_index], None)?;
+            Ok(self.proposal_weight(voter, &proposal).0)
+        }
+
+        /// Check whether `voter` could spend `credits_spent` on an option of a
+        /// quadratic proposal without changing state. Returns the votes the
+        /// option would receive, or the error `vote_quadratic` would fail with.
+        #[ink(message)]
+        pub fn dry_run_vote_quadratic(
+            &self,
+            proposal_id: u32,
+            option_index: u32,
+            credits_spent: u128,
+            voter: H160,
+        ) -> Result<u128> {
+            self.validate_vote(voter, proposal_id, &[option_index], Some(credits_spent))?;
+            Ok(Self::isqrt(credits_spent))
+        }
+
+        /// Vote on a proposal and return how the vote landed
+        #[ink(message)]
+        pub fn vote_with_receipt(&mut self, proposal_id: u32, option_index: u32) -> Result<VoteReceipt> {
+            self.cast_vote(proposal_id, ink::prelude::vec![option_index], None, 0)?;
+
+            let vote = self.votes.get((proposal_id, self.env().caller()))
+                .ok_or(Error::NoExistingVote)?;
             let proposal = self.proposals.get(proposal_id)
                 .ok_or(Error::ProposalNotFound)?;
 
-            let quorum_percentage = proposal.governance_params.quorum_threshold.to_percentage();
-            let quorum_required = (self.total_voters as u128)
-                .saturating_mul(quorum_percentage as u128)
-                .saturating_div(100);
-            let total_votes: u128 = proposal.vote_counts.iter().sum();
+            Ok(VoteReceipt {
+                proposal_id,
+                option_index,
+                weight: vote.weight,
+                new_total_for_option: proposal.vote_counts[option_index as usize],
+                timestamp: vote.timestamp,
+            })
+        }
+
+        /// Approve one or more options on an approval-mode proposal
+        #[ink(message)]
+        pub fn vote_approval(&mut self, proposal_id: u32, option_indices: Vec<u32>) -> Result<()> {
+            if option_indices.is_empty() {
+                return Err(Error::InvalidOptionIndex);
+            }
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+            if proposal.governance_params.voting_mode != VotingMode::Approval {
+                return Err(Error::InvalidVotingMode);
+            }
+            self.cast_vote(proposal_id, option_indices, None, 0)
+        }
 
-            Ok(total_votes >= quorum_required)
+        /// Vote and commit the vote for `lock_blocks`, multiplying its weight by
+        /// one plus a whole `CONVICTION_PERIOD` locked, up to
+        /// `MAX_CONVICTION_MULTIPLIER`. Until the lock ends the vote cannot be
+        /// changed or revoked, and the voter cannot unlock deposits,
+        /// deregister, delegate or have their weight reduced. The lock is
+        /// released early if the proposal is cancelled, rejected, vetoed or
+        /// expires. Locks longer than `MAX_CONVICTION_LOCK` are rejected.
+        #[ink(message)]
+        pub fn vote_with_conviction(&mut self, proposal_id: u32, option_index: u32, lock_blocks: u32) -> Result<()> {
+            if lock_blocks > MAX_CONVICTION_LOCK {
+                return Err(Error::ConvictionLockTooLong);
+            }
+            self.cast_vote(proposal_id, ink::prelude::vec![option_index], None, lock_blocks)
         }
 
-        /// Get proposal results
+        /// Spend credits on an option of a quadratic proposal. The option
+        /// receives the integer square root of `credits_spent` in votes.
         #[ink(message)]
-        pub fn get_proposal_results(&self, proposal_id: u32) -> Result<ProposalResults> {
+        pub fn vote_quadratic(&mut self, proposal_id: u32, option_index: u32, credits_spent: u128) -> Result<()> {
+            self.cast_vote(proposal_id, ink::prelude::vec![option_index], Some(credits_spent), 0)
+        }
+
+        /// Rank options of a ranked-choice proposal in order of preference.
+        /// The ranking need not include every option.
+        #[ink(message)]
+        pub fn vote_ranked(&mut self, proposal_id: u32, ranking: Vec<u32>) -> Result<()> {
+            if ranking.is_empty() {
+                return Err(Error::InvalidOptionIndex);
+            }
             let proposal = self.proposals.get(proposal_id)
                 .ok_or(Error::ProposalNotFound)?;
+            if proposal.governance_params.voting_mode != VotingMode::RankedChoice {
+                return Err(Error::InvalidVotingMode);
+            }
+            self.cast_vote(proposal_id, ranking, None, 0)
+        }
 
-            let quorum_percentage = proposal.governance_params.quorum_threshold.to_percentage();
-            let quorum_required = (self.total_voters as u128)
-                .saturating_mul(quorum_percentage as u128)
-                .saturating_div(100);
-            let total_votes: u128 = proposal.vote_counts.iter().sum();
-            let quorum_reached = total_votes >= quorum_required;
+        /// Abstain on a proposal: counts toward quorum but not toward any option
+        #[ink(message)]
+        pub fn abstain(&mut self, proposal_id: u32) -> Result<()> {
+            self.cast_vote(proposal_id, Vec::new(), None, 0)
+        }
 
-            // Find winning option
-            let mut max_votes = 0u128;
-            let mut winning_index = 0u32;
-            let mut winning_option = None;
+        /// Move an existing vote to a different option while voting is open
+        #[ink(message)]
+        pub fn change_vote(&mut self, proposal_id: u32, new_option_index: u32) -> Result<()> {
+            self.ensure_not_executing()?;
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            let current_block = self.env().block_number();
 
-            for (index, &votes) in proposal.vote_counts.iter().enumerate() {
-                if votes > max_votes {
-                    max_votes = votes;
-                    winning_index = u32::try_from(index).unwrap_or(0);
-                }
+            let mut proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+
+            // Check if proposal is active
+            if proposal.status != ProposalStatus::Active {
+                return Err(Error::ProposalNotActive);
             }
 
-            if max_votes > 0 {
-                winning_option = Some((
-                    winning_index,
-                    proposal.voting_options.options[winning_index as usize].clone(),
-                    max_votes,
-                ));
+            // Check if voting period has ended
+            if current_block > proposal.voting_end {
+                return Err(Error::VotingPeriodEnded);
             }
 
-            Ok(ProposalResults {
-                proposal_id,
-                vote_counts: proposal.vote_counts.clone(),
-                total_votes,
-                quorum_required,
-                quorum_reached,
-                winning_option,
-            })
+            let mut vote = self.votes.get((proposal_id, caller))
+                .ok_or(Error::NoExistingVote)?;
+
+            // The voter must still be eligible to move their weight
+            self.ensure_active_voter(caller)?;
+            if !self.is_eligible_for(proposal_id, caller) {
+                return Err(Error::NotEligibleForProposal);
+            }
+
+            // Proposers cannot steer their own proposal while that is forbidden
+            if !self.allow_proposer_vote && caller == proposal.proposer {
+                return Err(Error::ProposerCannotVote);
+            }
+
+            // Conviction votes stay committed until their lock ends
+            if current_block < vote.lock_until {
+                return Err(Error::VoteLocked);
+            }
+
+            // Validate option index
+            let new_idx = new_option_index as usize;
+            if new_idx >= proposal.voting_options.options.len() {
+                return Err(Error::InvalidOptionIndex);
+            }
+
+            // Move the voter's weight from the old choice to the new one
+            let previous_leader = Self::leading_option(&proposal);
+            Self::withdraw_vote_weight(&mut proposal, &vote);
+            proposal.vote_counts[new_idx] = proposal.vote_counts[new_idx].saturating_add(vote.weight);
+            self.record_lead_change(&proposal, previous_leader);
+
+            vote.choice = VoteChoice {
+                option_index: new_option_index,
+                option_text: proposal.voting_options.options[new_idx].clone(),
+            };
+            if matches!(
+                proposal.governance_params.voting_mode,
+                VotingMode::Approval | VotingMode::RankedChoice
+            ) {
+                vote.selections = ink::prelude::vec![new_option_index];
+            }
+            vote.timestamp = current_block;
+            self.votes.insert((proposal_id, caller), &vote);
+
+            // Resolve immediately if the outcome can no longer change
+            if self.resolve_on_vote && self.try_early_resolution(&mut proposal) {
+                self.finalize_proposal(&proposal);
+            }
+
+            self.proposals.insert(proposal_id, &proposal);
+            self.last_activity_block = current_block;
+
+            if self.emits(EventLevel::All) {
+                self.env().emit_event(VoteCast {
+                    proposal_id,
+                    voter: caller,
+                    option_index: new_option_index,
+                    weight: vote.weight,
+                });
+            }
+
+            Ok(())
         }
 
-        /// Get voting options for a proposal
+        /// Withdraw the caller's vote from an open proposal, removing its
+        /// weight from the tallies and quorum. Delegators whose weight the
+        /// vote carried may vote again themselves, and credits spent on a
+        /// quadratic vote are refunded.
         #[ink(message)]
-        pub fn get_voting_options(&self, proposal_id: u32) -> Result<VotingOptions> {
-            let proposal = self.proposals.get(proposal_id)
+        pub fn revoke_vote(&mut self, proposal_id: u32) -> Result<()> {
+            self.ensure_not_executing()?;
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            let current_block = self.env().block_number();
+
+            let mut proposal = self.proposals.get(proposal_id)
                 .ok_or(Error::ProposalNotFound)?;
-            Ok(proposal.voting_options.clone())
+
+            // Check if proposal is active
+            if proposal.status != ProposalStatus::Active {
+                return Err(Error::ProposalNotActive);
+            }
+
+            // Check if voting period has ended
+            if current_block > proposal.voting_end {
+                return Err(Error::VotingPeriodEnded);
+            }
+
+            let vote = self.votes.get((proposal_id, caller))
+                .ok_or(Error::NoExistingVote)?;
+
+            // Conviction votes stay committed until their lock ends
+            if current_block < vote.lock_until {
+                return Err(Error::VoteLocked);
+            }
+            self.votes.remove((proposal_id, caller));
+            if vote.credits_spent > 0 {
+                let balance = self.credits.get(caller).unwrap_or(0);
+                self.credits.insert(caller, &balance.saturating_add(vote.credits_spent));
+            }
+
+            let previous_leader = Self::leading_option(&proposal);
+            Self::withdraw_vote_weight(&mut proposal, &vote);
+            proposal.total_voters = proposal.total_voters.saturating_sub(1);
+            proposal.participating_weight = proposal.participating_weight.saturating_sub(vote.base_weight);
+            self.record_lead_change(&proposal, previous_leader);
+            self.proposals.insert(proposal_id, &proposal);
+
+            // Release the delegated weight this vote carried, even from
+            // delegators who have since undelegated or moved out of depth
+            for &delegator in &vote.carried {
+                if self.carried_votes.get((proposal_id, delegator)) == Some(caller) {
+                    self.carried_votes.remove((proposal_id, delegator));
+                }
+            }
+
+            let mut voters = self.proposal_voters.get(proposal_id).unwrap_or_default();
+            voters.retain(|&voter| voter != caller);
+            self.proposal_voters.insert(proposal_id, &voters);
+            let mut history = self.voter_history.get(caller).unwrap_or_default();
+            history.retain(|&id| id != proposal_id);
+            self.voter_history.insert(caller, &history);
+
+            if proposal_id >= self.first_eligible_proposal.get(caller).unwrap_or(u32::MAX) {
+                let cast = self.eligible_votes_cast.get(caller).unwrap_or(0);
+                self.eligible_votes_cast.insert(caller, &cast.saturating_sub(1));
+            }
+
+            self.last_activity_block = current_block;
+            Ok(())
         }
 
-        /// Get detailed results with option names
+        /// Update proposal status (can be called by anyone)
         #[ink(message)]
-        pub fn get_detailed_results(&self, proposal_id: u32) -> Result<Vec<(String, u128)>> {
+        pub fn update_proposal_status(&mut self, proposal_id: u32) -> Result<()> {
+            self.ensure_not_executing()?;
+            let current_block = self.env().block_number();
             let proposal = self.proposals.get(proposal_id)
                 .ok_or(Error::ProposalNotFound)?;
+            self.apply_pending_transition(proposal, current_block);
+            Ok(())
+        }
 
-            let mut results = Vec::new();
-            for (index, &votes) in proposal.vote_counts.iter().enumerate() {
-                results.push((
-                    proposal.voting_options.options[index].clone(),
-                    votes,
-                ));
+        /// Finalize active proposals whose voting period has ended and expire
+        /// passed proposals whose execution window has closed, scanning at
+        /// most `MAX_REFRESH_BATCH` proposals from position `start`.
+        /// Returns how many transitioned and the position to pass next, or
+        /// `None` once the last proposal has been scanned.
+        #[ink(message)]
+        pub fn refresh_all_statuses(&mut self, start: u32) -> Result<(u32, Option<u32>)> {
+            self.ensure_not_executing()?;
+            let current_block = self.env().block_number();
+            let mut transitioned: u32 = 0;
+            let end = start.saturating_add(MAX_REFRESH_BATCH);
+
+            for position in start..end {
+                let Some(&proposal_id) = self.proposal_ids.get(position as usize) else {
+                    return Ok((transitioned, None));
+                };
+                let Some(proposal) = self.proposals.get(proposal_id) else {
+                    continue;
+                };
+                let due = match proposal.status {
+                    ProposalStatus::Active => current_block > proposal.voting_end,
+                    ProposalStatus::Passed => Self::execution_window_closed(&proposal, current_block),
+                    _ => false,
+                };
+                if !due {
+                    continue;
+                }
+                if self.apply_pending_transition(proposal, current_block) {
+                    transitioned = transitioned.saturating_add(1);
+                }
             }
+            let more = (end as usize) < self.proposal_ids.len();
+            Ok((transitioned, more.then_some(end)))
+        }
 
-            Ok(results)
+        /// Status a proposal would have if `update_proposal_status` were
+        /// called now; nothing is written
+        #[ink(message)]
+        pub fn get_effective_status(&self, proposal_id: u32) -> Result<ProposalStatus> {
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+            Ok(self.pending_transition(&proposal, self.env().block_number())
+                .map_or(proposal.status, |(status, _)| status))
         }
 
-        /// Get the winning option and vote count
+        /// Update a proposal's status and, if it passed and the caller could
+        /// execute it right now (see `get_my_executable`), execute it in the
+        /// same call. Returns the resulting status. A passed proposal that is
+        /// not ready, e.g. behind others in the queue or waiting on its
+        /// dependency, stays `Passed`; a failed execution reverts the whole call.
         #[ink(message)]
-        pub fn get_winning_option(&self, proposal_id: u32) -> Result<Option<(String, u128)>> {
+        pub fn finalize_and_execute(&mut self, proposal_id: u32) -> Result<ProposalStatus> {
+            self.update_proposal_status(proposal_id)?;
             let proposal = self.proposals.get(proposal_id)
                 .ok_or(Error::ProposalNotFound)?;
 
-            let mut max_votes = 0u128;
-            let mut winning_index = 0usize;
+            if self.is_executable_by(&proposal, self.env().caller()) {
+                self.execute_proposal(proposal_id)?;
+                return Ok(ProposalStatus::Executed);
+            }
+            Ok(proposal.status)
+        }
+
+        /// Execute a passed proposal, paying out its treasury action if it has one.
+        ///
+        /// Only the owner and `Executor` accounts may execute by default. The
+        /// owner can call `set_restrict_execution(false)` to let anyone execute,
+        /// for example so keepers can run passed proposals without a role.
+        #[ink(message)]
+        pub fn execute_proposal(&mut self, proposal_id: u32) -> Result<()> {
+            // Refuse calls made back into the contract while a proposal executes
+            self.ensure_not_executing()?;
+            self.executing.set(&true);
+            let result = self.execute_passed_proposal(proposal_id);
+            self.executing.set(&false);
+            result
+        }
+
+        /// Body of `execute_proposal`, run under the reentrancy guard. All
+        /// checks and state changes happen before any external call.
+        fn execute_passed_proposal(&mut self, proposal_id: u32) -> Result<()> {
+            self.ensure_not_paused()?;
+            if !self.can_execute(self.env().caller()) {
+                return Err(Error::NotAuthorized);
+            }
+
+            let current_block = self.env().block_number();
+            let mut proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+
+            // Check if proposal is passed
+            if proposal.status != ProposalStatus::Passed {
+                return Err(Error::ProposalNotReadyForExecution);
+            }
 
-            for (index, &votes) in proposal.vote_counts.iter().enumerate() {
-                if votes > max_votes {
-                    max_votes = votes;
-                    winning_index = index;
+            // A dependent proposal waits for its dependency to execute
+            if let Some(dependency) = proposal.depends_on {
+                let executed = self.proposals.get(dependency)
+                    .is_some_and(|dependency| dependency.status == ProposalStatus::Executed);
+                if !executed {
+                    return Err(Error::DependencyNotExecuted);
                 }
             }
 
-            if max_votes > 0 {
-                Ok(Some((
-                    proposal.voting_options.options[winning_index].clone(),
-                    max_votes,
-                )))
-            } else {
-                Ok(None)
+            // Passed proposals execute in the order they passed
+            if self.execution_queue.first() != Some(&proposal_id) {
+                return Err(Error::NotNextInQueue);
             }
-        }
 
-        /// Check if an account is a registered voter
-        #[ink(message)]
-        pub fn is_registered_voter(&self, account: H160) -> bool {
-            self.registered_voters.get(account).is_some()
+            // Check if execution delay has passed
+            if current_block < proposal.execution_time {
+                return Err(Error::ExecutionDelayNotMet);
+            }
+
+            // Check if execution window is still open
+            if Self::execution_window_closed(&proposal, current_block) {
+                return Err(Error::ExecutionWindowClosed);
+            }
+
+            // Check the treasury can cover the payout, if any
+            if let Some(action) = &proposal.treasury_action {
+                self.ensure_payout_covered(action)?;
+            }
+
+            // Update status to executed
+            let executor = self.env().caller();
+            proposal.status = ProposalStatus::Executed;
+            proposal.executed_by = Some(executor);
+            proposal.executed_at = Some(current_block);
+            self.proposals.insert(proposal_id, &proposal);
+            self.record_status_change(&proposal, ProposalStatus::Passed);
+            self.execution_queue.remove(0);
+            self.settle_dependents(proposal_id);
+            self.last_activity_block = current_block;
+
+            // Pay out the treasury action, if any
+            if let Some(action) = &proposal.treasury_action {
+                self.env()
+                    .transfer(action.recipient, U256::from(action.amount))
+                    .map_err(|_| Error::TransferFailed)?;
+            }
+
+            // Make the attached contract call, if any; a failure reverts the execution
+            if let Some(call) = &proposal.execution_call {
+                if !self.invoke_execution_call(call) {
+                    return Err(Error::ExecutionCallFailed);
+                }
+            }
+
+            if self.emits(EventLevel::Critical) {
+                self.env().emit_event(ProposalExecuted {
+                    id: proposal_id,
+                    executor,
+                    block: current_block,
+                });
+            }
+
+            // Notify the execution hook, if any
+            if let Some(hook) = self.execution_hook {
+                if !self.notify_execution_hook(hook, proposal_id) && self.require_hook_success {
+                    return Err(Error::HookFailed);
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Withdraw an active proposal (proposer or owner). The deposit is
+        /// forfeited unless clean cancellations are refunded and the proposer
+        /// cancels before any votes.
+        #[ink(message)]
+        pub fn cancel_proposal(&mut self, proposal_id: u32) -> Result<()> {
+            self.ensure_not_executing()?;
+            let caller = self.env().caller();
+            let mut proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+
+            if caller != proposal.proposer && caller != self.owner {
+                return Err(Error::NotAuthorized);
+            }
+
+            if proposal.status != ProposalStatus::Active {
+                return Err(Error::ProposalNotActive);
+            }
+
+            proposal.status = ProposalStatus::Cancelled;
+            self.proposals.insert(proposal_id, &proposal);
+            self.record_status_change(&proposal, ProposalStatus::Active);
+            self.settle_dependents(proposal_id);
+
+            // Optionally refund a proposer withdrawing before any votes
+            if self.refund_clean_cancellations && caller == proposal.proposer && proposal.total_voters == 0 {
+                if let Some((depositor, amount)) = self.proposal_deposits.get(proposal_id) {
+                    self.proposal_deposits.remove(proposal_id);
+                    self.total_held_deposits = self.total_held_deposits.saturating_sub(amount);
+                    self.env()
+                        .transfer(depositor, U256::from(amount))
+                        .map_err(|_| Error::TransferFailed)?;
+                }
+            } else {
+                self.forfeit_deposit(proposal_id);
+            }
+            Ok(())
+        }
+
+        /// Push a proposal's voting end and execution time back (proposer or
+        /// owner, while voting is open). Extensions add up to at most
+        /// `MAX_VOTING_EXTENSION` blocks per proposal.
+        #[ink(message)]
+        pub fn extend_voting_period(&mut self, proposal_id: u32, additional_blocks: u32) -> Result<()> {
+            self.ensure_not_executing()?;
+            let caller = self.env().caller();
+            let mut proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+
+            if caller != proposal.proposer && caller != self.owner {
+                return Err(Error::NotAuthorized);
+            }
+
+            if proposal.status != ProposalStatus::Active {
+                return Err(Error::ProposalNotActive);
+            }
+
+            if self.env().block_number() > proposal.voting_end {
+                return Err(Error::VotingPeriodEnded);
+            }
+
+            let extended = self.voting_extensions.get(proposal_id).unwrap_or(0)
+                .saturating_add(additional_blocks);
+            if extended > MAX_VOTING_EXTENSION {
+                return Err(Error::ExtensionTooLarge);
+            }
+
+            proposal.voting_end = proposal.voting_end.saturating_add(additional_blocks);
+            proposal.execution_time = proposal.execution_time.saturating_add(additional_blocks);
+            self.proposals.insert(proposal_id, &proposal);
+            self.voting_extensions.insert(proposal_id, &extended);
+
+            if self.emits(EventLevel::All) {
+                self.env().emit_event(VotingPeriodExtended {
+                    proposal_id,
+                    new_voting_end: proposal.voting_end,
+                });
+            }
+            Ok(())
+        }
+
+        /// Refund a proposal's deposit to its depositor once voting has
+        /// resolved. Deposits of cancelled proposals stay in the treasury.
+        #[ink(message)]
+        pub fn claim_deposit(&mut self, proposal_id: u32) -> Result<()> {
+            self.ensure_not_executing()?;
+            let caller = self.env().caller();
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+            let (depositor, amount) = self.proposal_deposits.get(proposal_id)
+                .ok_or(Error::NoDeposit)?;
+
+            if caller != depositor {
+                return Err(Error::NotAuthorized);
+            }
+
+            if matches!(proposal.status, ProposalStatus::Active | ProposalStatus::Cancelled) {
+                return Err(Error::DepositNotRefundable);
+            }
+
+            self.proposal_deposits.remove(proposal_id);
+            self.total_held_deposits = self.total_held_deposits.saturating_sub(amount);
+            self.env()
+                .transfer(depositor, U256::from(amount))
+                .map_err(|_| Error::TransferFailed)?;
+            Ok(())
+        }
+
+        /// Get the balance treasury payouts may draw on: the contract balance
+        /// less locked funds and refundable proposal deposits. The operating
+        /// reserve is not subtracted.
+        #[ink(message)]
+        pub fn get_spendable_balance(&self) -> Balance {
+            let held = U256::from(self.total_locked.saturating_add(self.total_held_deposits));
+            Balance::try_from(self.env().balance().saturating_sub(held)).unwrap_or(Balance::MAX)
+        }
+
+        /// Get the deposit held for a proposal, as (depositor, amount)
+        #[ink(message)]
+        pub fn get_proposal_deposit(&self, proposal_id: u32) -> Option<(H160, Balance)> {
+            self.proposal_deposits.get(proposal_id)
+        }
+
+        /// Hand authorship of an active proposal to another account
+        /// (proposer or owner)
+        #[ink(message)]
+        pub fn transfer_proposal(&mut self, proposal_id: u32, new_proposer: H160) -> Result<()> {
+            self.ensure_not_executing()?;
+            let caller = self.env().caller();
+            let mut proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+
+            if caller != proposal.proposer && caller != self.owner {
+                return Err(Error::NotAuthorized);
+            }
+
+            if proposal.status != ProposalStatus::Active {
+                return Err(Error::ProposalNotActive);
+            }
+
+            proposal.proposer = new_proposer;
+            self.proposals.insert(proposal_id, &proposal);
+            Ok(())
+        }
+
+        /// Replace the treasury payout of a passed proposal before its execution
+        /// time (proposer or owner, only if amendment was allowed at creation).
+        /// The amount may only go down, and a new recipient must be on the
+        /// approved list even when recipients are not otherwise restricted,
+        /// so no amendment can redirect or enlarge what voters approved.
+        #[ink(message)]
+        pub fn amend_execution_payload(
+            &mut self,
+            proposal_id: u32,
+            new_payload: TreasuryAction,
+        ) -> Result<()> {
+            self.ensure_not_executing()?;
+            let caller = self.env().caller();
+            let current_block = self.env().block_number();
+            let mut proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+
+            if caller != proposal.proposer && caller != self.owner {
+                return Err(Error::NotAuthorized);
+            }
+
+            if !proposal.governance_params.allow_payload_amendment {
+                return Err(Error::AmendmentNotAllowed);
+            }
+
+            if proposal.proposal_type != ProposalType::Treasury {
+                return Err(Error::InvalidProposal);
+            }
+
+            if proposal.status != ProposalStatus::Passed {
+                return Err(Error::ProposalNotPassed);
+            }
+
+            // Amendments are only possible during the execution delay
+            if current_block >= proposal.execution_time {
+                return Err(Error::AmendmentWindowClosed);
+            }
+
+            let Some(approved) = &proposal.treasury_action else {
+                return Err(Error::InvalidProposal);
+            };
+            if new_payload.amount > approved.amount {
+                return Err(Error::AmendmentIncreasesAmount);
+            }
+            if new_payload.recipient != approved.recipient
+                && self.approved_recipients.get(new_payload.recipient).is_none()
+            {
+                return Err(Error::RecipientNotApproved);
+            }
+
+            proposal.treasury_action = Some(new_payload);
+            self.proposals.insert(proposal_id, &proposal);
+
+            Ok(())
+        }
+
+        /// Veto a passed proposal before its execution time (guardian only)
+        #[ink(message)]
+        pub fn veto_proposal(&mut self, proposal_id: u32) -> Result<()> {
+            self.ensure_not_executing()?;
+            let caller = self.env().caller();
+            let current_block = self.env().block_number();
+
+            if self.guardian != Some(caller) {
+                return Err(Error::NotAuthorized);
+            }
+
+            let mut proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+
+            if proposal.status != ProposalStatus::Passed {
+                return Err(Error::ProposalNotPassed);
+            }
+
+            // Vetoes are only possible during the execution delay
+            if current_block >= proposal.execution_time {
+                return Err(Error::VetoWindowClosed);
+            }
+
+            proposal.status = ProposalStatus::Vetoed;
+            self.proposals.insert(proposal_id, &proposal);
+            self.record_status_change(&proposal, ProposalStatus::Passed);
+            self.remove_from_execution_queue(proposal_id);
+            self.settle_dependents(proposal_id);
+            self.veto_log.insert(self.veto_count, &(proposal_id, caller));
+            self.veto_count = self.veto_count.saturating_add(1);
+
+            Ok(())
+        }
+
+        /// Expire the proposal at the head of the execution queue once its
+        /// execution time has passed (owner or guardian only), so a head
+        /// that cannot run does not hold up every proposal behind it. A head
+        /// whose window has closed, whose payout the treasury cannot cover
+        /// or whose dependency can never execute may be expired at once.
+        /// Any other head, e.g. one calling a reverting target, only after
+        /// `QUEUE_EXPIRY_GRACE_PERIOD`. Its dependents expire with it.
+        #[ink(message)]
+        pub fn expire_queue_head(&mut self, proposal_id: u32) -> Result<()> {
+            self.ensure_not_executing()?;
+            let caller = self.env().caller();
+
+            if caller != self.owner && self.guardian != Some(caller) {
+                return Err(Error::NotAuthorized);
+            }
+            if self.execution_queue.first() != Some(&proposal_id) {
+                return Err(Error::NotNextInQueue);
+            }
+
+            let mut proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+
+            // Before the execution time the guardian can veto instead
+            let current_block = self.env().block_number();
+            if current_block < proposal.execution_time {
+                return Err(Error::ExecutionDelayNotMet);
+            }
+
+            // A head that may still execute gets a grace period first
+            if !self.can_never_execute(&proposal, current_block)
+                && current_block < proposal.execution_time.saturating_add(QUEUE_EXPIRY_GRACE_PERIOD)
+            {
+                return Err(Error::QueueHeadExecutable);
+            }
+
+            proposal.status = ProposalStatus::Expired;
+            self.proposals.insert(proposal_id, &proposal);
+            self.record_status_change(&proposal, ProposalStatus::Passed);
+            self.remove_from_execution_queue(proposal_id);
+            self.settle_dependents(proposal_id);
+
+            Ok(())
+        }
+
+        /// Get a specific proposal
+        #[ink(message)]
+        pub fn get_proposal(&self, proposal_id: u32) -> Result<Proposal> {
+            self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)
+        }
+
+        /// Passed proposals awaiting execution; only the first may execute
+        #[ink(message)]
+        pub fn get_execution_queue(&self) -> Vec<u32> {
+            self.execution_queue.clone()
+        }
+
+        /// Get all proposal IDs
+        #[ink(message)]
+        pub fn get_all_proposal_ids(&self) -> Vec<u32> {
+            self.proposal_ids.clone()
+        }
+
+        /// Get up to `limit` proposal IDs (capped at 50) starting at position `start`
+        #[ink(message)]
+        pub fn get_proposal_ids_paged(&self, start: u32, limit: u32) -> Vec<u32> {
+            self.proposal_ids
+                .iter()
+                .skip(start as usize)
+                .take(limit.min(50) as usize)
+                .copied()
+                .collect()
+        }
+
+        /// Get up to `limit` proposals (capped at 50) starting at position `start`
+        #[ink(message)]
+        pub fn get_proposals_paged(&self, start: u32, limit: u32) -> Vec<Proposal> {
+            self.get_proposal_ids_paged(start, limit)
+                .into_iter()
+                .filter_map(|proposal_id| self.proposals.get(proposal_id))
+                .collect()
+        }
+
+        /// Get IDs of all proposals with the given status. Reads every
+        /// proposal, so the cost grows with the proposal count; use
+        /// `get_proposals_filtered` for a bounded page.
+        #[ink(message)]
+        pub fn get_proposals_by_status(&self, status: ProposalStatus) -> Vec<u32> {
+            self.proposal_ids
+                .iter()
+                .filter_map(|&proposal_id| self.proposals.get(proposal_id))
+                .filter(|proposal| proposal.status == status)
+                .map(|proposal| proposal.id)
+                .collect()
+        }
+
+        /// Get IDs of all proposals of the given type. Reads every proposal,
+        /// so the cost grows with the proposal count; use
+        /// `get_proposals_filtered` for a bounded page.
+        #[ink(message)]
+        pub fn get_proposals_by_type(&self, proposal_type: ProposalType) -> Vec<u32> {
+            self.proposal_ids
+                .iter()
+                .filter_map(|&proposal_id| self.proposals.get(proposal_id))
+                .filter(|proposal| proposal.proposal_type == proposal_type)
+                .map(|proposal| proposal.id)
+                .collect()
+        }
+
+        /// Get IDs of proposals matching an optional status and type, where
+        /// `None` matches any. Skips `start` matches and returns at most
+        /// `limit` (capped at 50).
+        #[ink(message)]
+        pub fn get_proposals_filtered(
+            &self,
+            status: Option<ProposalStatus>,
+            proposal_type: Option<ProposalType>,
+            start: u32,
+            limit: u32,
+        ) -> Vec<u32> {
+            self.proposal_ids
+                .iter()
+                .filter_map(|&proposal_id| self.proposals.get(proposal_id))
+                .filter(|proposal| status.as_ref().is_none_or(|s| *s == proposal.status))
+                .filter(|proposal| proposal_type.as_ref().is_none_or(|t| *t == proposal.proposal_type))
+                .skip(start as usize)
+                .take(limit.min(50) as usize)
+                .map(|proposal| proposal.id)
+                .collect()
+        }
+
+        /// Get the IDs of the proposals the caller could execute right now,
+        /// skipping `start` and returning at most `limit` (capped at 50). Only
+        /// the head of the execution queue can run, so this holds at most one
+        /// ID: the head, once its delay has passed and its dependency, if
+        /// any, has executed.
+        #[ink(message)]
+        pub fn get_my_executable(&self, start: u32, limit: u32) -> Vec<u32> {
+            let caller = self.env().caller();
+            self.execution_queue
+                .first()
+                .and_then(|&proposal_id| self.proposals.get(proposal_id))
+                .filter(|proposal| self.is_executable_by(proposal, caller))
+                .map(|proposal| proposal.id)
+                .into_iter()
+                .skip(start as usize)
+                .take(limit.min(50) as usize)
+                .collect()
+        }
+
+        /// Get the recorded lead changes of a proposal, as
+        /// (block, new_leader_index), oldest first
+        #[ink(message)]
+        pub fn get_lead_changes(&self, proposal_id: u32) -> Result<Vec<LeadChange>> {
+            if !self.proposals.contains(proposal_id) {
+                return Err(Error::ProposalNotFound);
+            }
+            Ok(self.lead_changes.get(proposal_id).unwrap_or_default())
+        }
+
+        /// Get user's vote on a proposal
+        #[ink(message)]
+        pub fn get_user_vote(&self, proposal_id: u32, user: H160) -> Result<Vote> {
+            self.votes.get((proposal_id, user))
+                .ok_or(Error::ProposalNotFound)
+        }
+
+        /// Get the accounts allowed to vote on a proposal, or `None` when
+        /// every registered voter may
+        #[ink(message)]
+        pub fn get_eligible_voters(&self, proposal_id: u32) -> Option<Vec<H160>> {
+            self.proposal_allowlists.get(proposal_id)
+        }
+
+        /// Get up to `limit` vote records (capped at 50) on a proposal, in
+        /// voting order, starting at position `start`
+        #[ink(message)]
+        pub fn get_proposal_votes(&self, proposal_id: u32, start: u32, limit: u32) -> Result<Vec<Vote>> {
+            if !self.proposals.contains(proposal_id) {
+                return Err(Error::ProposalNotFound);
+            }
+
+            Ok(self.proposal_voters
+                .get(proposal_id)
+                .unwrap_or_default()
+                .into_iter()
+                .skip(start as usize)
+                .take(limit.min(50) as usize)
+                .filter_map(|voter| self.votes.get((proposal_id, voter)))
+                .collect())
+        }
+
+        /// Get the IDs of the proposals an account voted on, in voting order
+        #[ink(message)]
+        pub fn get_voter_history(&self, voter: H160) -> Vec<u32> {
+            self.voter_history.get(voter).unwrap_or_default()
+        }
+
+        /// Get an account's vote records, in voting order. Reads one vote per
+        /// proposal in the history.
+        #[ink(message)]
+        pub fn get_voter_votes(&self, voter: H160) -> Vec<Vote> {
+            self.get_voter_history(voter)
+                .into_iter()
+                .filter_map(|proposal_id| self.votes.get((proposal_id, voter)))
+                .collect()
+        }
+
+        /// Get contract statistics
+        #[ink(message)]
+        pub fn get_stats(&self) -> ContractStats {
+            let mut active_proposals: u32 = 0;
+            let mut executed_proposals: u32 = 0;
+            let mut total_votes_cast: u128 = 0;
+            let mut participation_bps_sum: u128 = 0;
+            let mut resolved_proposals: u128 = 0;
+
+            for &proposal_id in &self.proposal_ids {
+                if let Some(proposal) = self.proposals.get(proposal_id) {
+                    match proposal.status {
+                        ProposalStatus::Active => active_proposals = active_proposals.saturating_add(1),
+                        ProposalStatus::Executed => executed_proposals = executed_proposals.saturating_add(1),
+                        _ => {}
+                    }
+                    let participation = Self::participation(&proposal);
+                    total_votes_cast = total_votes_cast.saturating_add(participation);
+
+                    // Cancelled proposals were never decided by a vote
+                    if !matches!(proposal.status, ProposalStatus::Active | ProposalStatus::Cancelled) {
+                        let (_, _, eligible_power) = Self::quorum_denominator(&proposal);
+                        let bps = participation
+                            .saturating_mul(10_000)
+                            .checked_div(eligible_power)
+                            .unwrap_or(0)
+                            .min(10_000);
+                        participation_bps_sum = participation_bps_sum.saturating_add(bps);
+                        resolved_proposals = resolved_proposals.saturating_add(1);
+                    }
+                }
+            }
+            let average_participation_bps = participation_bps_sum
+                .checked_div(resolved_proposals)
+                .and_then(|average| u32::try_from(average).ok())
+                .unwrap_or(0);
+
+            ContractStats {
+                total_proposals: u32::try_from(self.proposal_ids.len()).unwrap_or(0),
+                active_proposals,
+                executed_proposals,
+                total_voters: self.total_voters,
+                total_votes_cast,
+                average_participation_bps,
+            }
+        }
+
+        /// Get (passed or executed count, total count) for proposals of a
+        /// type, read from counters kept current on every status change
+        #[ink(message)]
+        pub fn get_pass_rate_by_type(&self, proposal_type: ProposalType) -> (u32, u32) {
+            let passed = self.status_counts.get((proposal_type.clone(), ProposalStatus::Passed)).unwrap_or(0)
+                .saturating_add(self.status_counts.get((proposal_type.clone(), ProposalStatus::Executed)).unwrap_or(0));
+            (passed, self.type_proposal_counts.get(proposal_type).unwrap_or(0))
+        }
+
+        /// Get the block at which a voter registered, if registered
+        #[ink(message)]
+        pub fn get_registered_at(&self, voter: H160) -> Option<u32> {
+            self.registered_at.get(voter)
+        }
+
+        /// Count current voters who registered at or after `block`. Reads one
+        /// count per registration block from `block` on.
+        #[ink(message)]
+        pub fn count_registered_since(&self, block: u32) -> u32 {
+            // Binary search for the first registration block at or after `block`
+            let (mut low, mut high) = (0, self.registration_block_count);
+            while low < high {
+                let mid = low + (high - low) / 2;
+                if self.registration_blocks.get(mid).unwrap_or(0) < block {
+                    low = mid + 1;
+                } else {
+                    high = mid;
+                }
+            }
+            (low..self.registration_block_count)
+                .filter_map(|position| self.registration_blocks.get(position))
+                .map(|registered| self.registration_counts.get(registered).unwrap_or(0))
+                .fold(0, u32::saturating_add)
+        }
+
+        /// Get total registered voters
+        #[ink(message)]
+        pub fn get_total_voters(&self) -> u32 {
+            self.total_voters
+        }
+
+        /// Get the current block number as seen by the contract
+        #[ink(message)]
+        pub fn current_block(&self) -> u32 {
+            self.env().block_number()
+        }
+
+        /// Get the block of the last governance activity (create, vote or execute)
+        #[ink(message)]
+        pub fn get_last_activity(&self) -> u32 {
+            self.last_activity_block
+        }
+
+        /// Get the block at which the contract was instantiated
+        #[ink(message)]
+        pub fn get_deployed_at(&self) -> u32 {
+            self.deployed_at_block
+        }
+
+        /// Get the number of blocks since the contract was instantiated
+        #[ink(message)]
+        pub fn blocks_since_deploy(&self) -> u32 {
+            self.env().block_number().saturating_sub(self.deployed_at_block)
+        }
+
+        /// Get the number of blocks left until a proposal's voting end block
+        /// (0 once it is reached)
+        #[ink(message)]
+        pub fn blocks_until_voting_end(&self, proposal_id: u32) -> Result<u32> {
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+            Ok(proposal.voting_end.saturating_sub(self.env().block_number()))
+        }
+
+        /// Get the number of blocks left until a proposal's execution delay
+        /// has elapsed (0 once it has)
+        #[ink(message)]
+        pub fn blocks_until_executable(&self, proposal_id: u32) -> Result<u32> {
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+            Ok(proposal.execution_time.saturating_sub(self.env().block_number()))
+        }
+
+        /// Check if proposal has reached quorum
+        #[ink(message)]
+        pub fn has_reached_quorum(&self, proposal_id: u32) -> Result<bool> {
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+
+            Ok(self.quorum_met(&proposal))
+        }
+
+        /// Get how much more participating weight a proposal needs to reach
+        /// quorum (0 once quorum is met), counting the absolute minimum too
+        #[ink(message)]
+        pub fn get_quorum_shortfall(&self, proposal_id: u32) -> Result<u128> {
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+
+            let required = self.quorum_required(&proposal)
+                .max(proposal.governance_params.min_absolute_weight);
+            Ok(required.saturating_sub(self.quorum_participation(&proposal)))
+        }
+
+        /// Get proposal results, from the cache once the proposal is finalized
+        #[ink(message)]
+        pub fn get_proposal_results(&self, proposal_id: u32) -> Result<ProposalResults> {
+            if let Some(results) = self.cached_results.get(proposal_id) {
+                return Ok(results);
+            }
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+
+            Ok(self.compute_results(&proposal))
+        }
+
+        /// Get the results cached when the proposal was finalized, if any
+        #[ink(message)]
+        pub fn get_cached_results(&self, proposal_id: u32) -> Option<ProposalResults> {
+            self.cached_results.get(proposal_id)
+        }
+
+        /// Tally a proposal's current counts into results
+        fn compute_results(&self, proposal: &Proposal) -> ProposalResults {
+            let quorum_required = self.quorum_required(proposal);
+            let total_votes = Self::option_votes(proposal);
+            let quorum_reached = self.quorum_met(proposal);
+
+            // No winner on a tie, matching `update_proposal_status`
+            let winning_option = self.winning_option(proposal).map(|index| {
+                (
+                    index,
+                    proposal.voting_options.options[index as usize].clone(),
+                    proposal.vote_counts[index as usize],
+                )
+            });
+
+            ProposalResults {
+                proposal_id: proposal.id,
+                vote_counts: proposal.vote_counts.clone(),
+                total_votes,
+                quorum_required,
+                quorum_reached,
+                winning_option,
+            }
+        }
+
+        /// Get the sum of a proposal's `vote_counts` plus abstentions, so
+        /// conviction multipliers and every approval selection are included
+        #[ink(message)]
+        pub fn get_total_votes(&self, proposal_id: u32) -> Result<u128> {
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+            Ok(Self::tallied_weight(&proposal))
+        }
+
+        /// Get voting options for a proposal
+        #[ink(message)]
+        pub fn get_voting_options(&self, proposal_id: u32) -> Result<VotingOptions> {
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+            Ok(proposal.voting_options.clone())
+        }
+
+        /// Get detailed results with option names
+        #[ink(message)]
+        pub fn get_detailed_results(&self, proposal_id: u32) -> Result<Vec<(String, u128)>> {
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+            let vote_counts = match self.cached_results.get(proposal_id) {
+                Some(results) => results.vote_counts,
+                None => proposal.vote_counts,
+            };
+
+            let mut results = Vec::new();
+            for (index, &votes) in vote_counts.iter().enumerate() {
+                results.push((
+                    proposal.voting_options.options[index].clone(),
+                    votes,
+                ));
+            }
+
+            Ok(results)
+        }
+
+        /// Get the winning option and vote count, or `None` without votes
+        /// or when the top tally is shared
+        #[ink(message)]
+        pub fn get_winning_option(&self, proposal_id: u32) -> Result<Option<(String, u128)>> {
+            if let Some(results) = self.cached_results.get(proposal_id) {
+                return Ok(results.winning_option.map(|(_, text, count)| (text, count)));
+            }
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+
+            Ok(self.winning_option(&proposal).map(|index| {
+                (
+                    proposal.voting_options.options[index as usize].clone(),
+                    proposal.vote_counts[index as usize],
+                )
+            }))
+        }
+
+        /// Percentage of the proposals a voter was eligible for (created after
+        /// their registration) on which they voted. Returns 0 with no eligible proposals.
+        #[ink(message)]
+        pub fn get_participation_rate(&self, voter: H160) -> u32 {
+            let Some(first_eligible) = self.first_eligible_proposal.get(voter) else {
+                return 0;
+            };
+
+            let eligible = self.next_proposal_id.saturating_sub(first_eligible);
+            if eligible == 0 {
+                return 0;
+            }
+
+            let cast = self.eligible_votes_cast.get(voter).unwrap_or(0);
+            cast.saturating_mul(100).saturating_div(eligible)
+        }
+
+        /// Check if an account is a registered voter
+        #[ink(message)]
+        pub fn is_registered_voter(&self, account: H160) -> bool {
+            self.registered_voters.get(account).is_some()
+        }
+
+        /// Blacklist or un-blacklist a registered voter (owner only)
+        #[ink(message)]
+        pub fn set_blacklisted(&mut self, voter: H160, blacklisted: bool) -> Result<()> {
+            self.ensure_owner()?;
+
+            if self.registered_voters.get(voter).is_none() {
+                return Err(Error::VoterNotRegistered);
+            }
+
+            let before = self.counted_power(voter);
+            let currently_blacklisted = self.blacklisted.get(voter).is_some();
+            if blacklisted && !currently_blacklisted {
+                self.blacklisted.insert(voter, &true);
+                self.total_blacklisted = self.total_blacklisted.saturating_add(1);
+            } else if !blacklisted && currently_blacklisted {
+                self.blacklisted.remove(voter);
+                self.total_blacklisted = self.total_blacklisted.saturating_sub(1);
+            }
+            self.sync_total_power(voter, before);
+
+            Ok(())
+        }
+
+        /// Get the total voting power of registered, non-blacklisted voters
+        #[ink(message)]
+        pub fn get_total_voting_power(&self) -> u128 {
+            self.total_voting_power
+        }
+
+        /// Set the voting weight of an account (owner only)
+        #[ink(message)]
+        pub fn set_voting_power(&mut self, voter: H160, power: u128) -> Result<()> {
+            self.ensure_owner()?;
+            self.apply_voting_power(voter, power)
+        }
+
+        /// Set the weights of several registered voters at once (owner only).
+        /// Unregistered accounts are skipped; returns how many weights were
+        /// set. At most 100 entries.
+        #[ink(message)]
+        pub fn set_voter_weights_batch(&mut self, entries: Vec<(H160, u128)>) -> Result<u32> {
+            self.ensure_owner()?;
+            if entries.len() > 100 {
+                return Err(Error::BatchTooLarge);
+            }
+
+            let mut applied: u32 = 0;
+            for (voter, power) in entries {
+                if self.registered_voters.get(voter).is_none() {
+                    continue;
+                }
+                self.apply_voting_power(voter, power)?;
+                applied = applied.saturating_add(1);
+            }
+            Ok(applied)
+        }
+
+        /// Configure the maximum weight change per update (owner only)
+        #[ink(message)]
+        pub fn set_weight_smoothing(&mut self, enabled: bool, max_weight_delta: u128) -> Result<()> {
+            self.ensure_owner()?;
+            self.smooth_weight_changes = enabled;
+            self.max_weight_delta = max_weight_delta;
+            Ok(())
+        }
+
+        /// Set a voter's weight for one proposal type (owner only)
+        #[ink(message)]
+        pub fn set_type_weight(
+            &mut self,
+            voter: H160,
+            proposal_type: ProposalType,
+            weight: u128,
+        ) -> Result<()> {
+            self.ensure_owner()?;
+            if weight < self.vote_weight(voter, &proposal_type) {
+                self.ensure_not_conviction_locked(voter)?;
+            }
+            if !self.type_weights.contains((voter, proposal_type.clone())) {
+                let voters = self.type_weighted_voters.get(&proposal_type).unwrap_or(0);
+                self.type_weighted_voters.insert(&proposal_type, &voters.saturating_add(1));
+            }
+            self.type_weights.insert((voter, proposal_type), &weight);
+            Ok(())
+        }
+
+        /// Get the current voting weight of an account: the explicit weight
+        /// if one is set, 1 for a registered voter without one, 0 otherwise
+        #[ink(message)]
+        pub fn get_voter_weight(&self, voter: H160) -> u128 {
+            if self.registered_voters.get(voter).is_none() {
+                return 0;
+            }
+            self.voting_power.get(voter).unwrap_or(1)
+        }
+
+        /// Weight a vote by `voter` on a proposal would carry right now. 0 for
+        /// accounts that cannot vote: unregistered, blacklisted, outside the
+        /// proposal's allowlist, delegated away, the proposer while proposers
+        /// may not vote, or already counted on the proposal. Otherwise modifiers apply in this order:
+        /// 1. the type-specific weight if set, else the general weight
+        /// 2. plus the general weight of delegators up to `max_delegation_depth`
+        ///    hops away whose weight has not been used on the proposal and who
+        ///    are eligible for it
+        ///
+        /// With maturity weighting, each of these contributions is first scaled
+        /// by its account's maturity. This is the weight counted toward quorum;
+        /// a conviction vote then multiplies it for the tally only.
+        ///
+        /// On quadratic proposals it is instead the tally a vote spending all
+        /// of the voter's credits would add, their integer square root, and
+        /// delegating does not zero it.
+        #[ink(message)]
+        pub fn compute_effective_weight(&self, voter: H160, proposal_id: u32) -> Result<u128> {
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+            let quadratic = proposal.governance_params.voting_mode == VotingMode::Quadratic;
+
+            if self.registered_voters.get(voter).is_none()
+                || self.blacklisted.get(voter).is_some()
+                || !self.is_eligible_for(proposal_id, voter)
+                || (!quadratic && self.delegations.contains(voter))
+                || (!self.allow_proposer_vote && voter == proposal.proposer)
+                || self.votes.contains((proposal_id, voter))
+                || self.carried_votes.contains((proposal_id, voter))
+            {
+                return Ok(0);
+            }
+            if quadratic {
+                return Ok(Self::isqrt(self.credits.get(voter).unwrap_or(0)));
+            }
+
+            Ok(self.proposal_weight(voter, &proposal).0)
+        }
+
+        /// Get an account's weight including weight delegated to it, followed
+        /// back at most `max_delegation_depth` hops. Accounts that delegated
+        /// their own weight away have none.
+        #[ink(message)]
+        pub fn get_effective_weight(&self, account: H160) -> u128 {
+            if self.delegations.contains(account) {
+                return 0;
+            }
+            self.counted_power(account)
+                .saturating_add(self.delegated_weight(account, self.max_delegation_depth))
+        }
+
+        /// Set how many delegation hops weight flows through (owner only).
+        /// Weight delegated from further away is not counted.
+        #[ink(message)]
+        pub fn set_max_delegation_depth(&mut self, depth: u32) -> Result<()> {
+            self.ensure_owner()?;
+            self.max_delegation_depth = depth;
+            Ok(())
+        }
+
+        /// Get how many delegation hops weight flows through
+        #[ink(message)]
+        pub fn get_max_delegation_depth(&self) -> u32 {
+            self.max_delegation_depth
+        }
+
+        /// Set how many blocks a deregistered voter waits before registering
+        /// again (owner only)
+        #[ink(message)]
+        pub fn set_reregister_cooldown(&mut self, blocks: u32) -> Result<()> {
+            self.ensure_owner()?;
+            self.reregister_cooldown_blocks = blocks;
+            Ok(())
+        }
+
+        /// Choose whether deregistering cancels the voter's active proposals (owner only)
+        #[ink(message)]
+        pub fn set_cancel_proposals_on_deregister(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.cancel_proposals_on_deregister = enabled;
+            Ok(())
+        }
+
+        /// Approve or revoke a treasury payout recipient (owner only)
+        #[ink(message)]
+        pub fn set_recipient_approved(&mut self, recipient: H160, approved: bool) -> Result<()> {
+            self.ensure_owner()?;
+            if approved {
+                self.approved_recipients.insert(recipient, &true);
+            } else {
+                self.approved_recipients.remove(recipient);
+            }
+            Ok(())
+        }
+
+        /// Check if an account is an approved treasury recipient
+        #[ink(message)]
+        pub fn is_approved_recipient(&self, recipient: H160) -> bool {
+            self.approved_recipients.get(recipient).is_some()
+        }
+
+        /// Restrict treasury payouts to approved recipients (owner only)
+        #[ink(message)]
+        pub fn set_restrict_recipients(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.restrict_recipients = enabled;
+            Ok(())
+        }
+
+        /// Grant or revoke the executor role (owner only)
+        #[ink(message)]
+        pub fn set_executor(&mut self, account: H160, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            if enabled {
+                self.grant_role(account, Role::Executor)
+            } else {
+                self.revoke_role(account, Role::Executor)
+            }
+        }
+
+        /// Check if an account holds the executor role
+        #[ink(message)]
+        pub fn is_executor(&self, account: H160) -> bool {
+            self.has_role(account, Role::Executor)
+        }
+
+        /// Grant a role to an account (owner or admin; only the owner grants Admin)
+        #[ink(message)]
+        pub fn grant_role(&mut self, account: H160, role: Role) -> Result<()> {
+            self.ensure_role_manager(role)?;
+            self.roles.insert((account, role), &true);
+            Ok(())
+        }
+
+        /// Revoke a role from an account (owner or admin; only the owner revokes Admin)
+        #[ink(message)]
+        pub fn revoke_role(&mut self, account: H160, role: Role) -> Result<()> {
+            self.ensure_role_manager(role)?;
+            self.roles.remove((account, role));
+            Ok(())
+        }
+
+        /// Check if an account holds a role. The owner holds every role.
+        #[ink(message)]
+        pub fn has_role(&self, account: H160, role: Role) -> bool {
+            account == self.owner || self.roles.get((account, role)).is_some()
+        }
+
+        /// Restrict proposal execution to executors, or open it to anyone
+        /// (owner only). Execution is restricted by default.
+        #[ink(message)]
+        pub fn set_restrict_execution(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.restrict_execution = enabled;
+            Ok(())
+        }
+
+        /// Allow or forbid proposers voting on their own proposals (owner
+        /// only). While forbidden, proposers can neither vote nor change an
+        /// earlier vote, but may still revoke one.
+        #[ink(message)]
+        pub fn set_allow_proposer_vote(&mut self, allowed: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.allow_proposer_vote = allowed;
+            Ok(())
+        }
+
+        /// Reject proposals whose abstentions outweigh actual votes (owner only)
+        #[ink(message)]
+        pub fn set_abstain_majority_rejects(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.abstain_majority_rejects = enabled;
+            Ok(())
+        }
+
+        /// Allow or disallow locking funds for weight (owner only)
+        #[ink(message)]
+        pub fn set_deposit_weighting(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.deposit_weighting = enabled;
+            Ok(())
+        }
+
+        /// Configure maturity weighting (owner only). When enabled, a voter's
+        /// weight starts at half and reaches full weight `blocks` after
+        /// registration. Quorum totals still count full weights.
+        #[ink(message)]
+        pub fn set_maturity_weighting(&mut self, enabled: bool, blocks: u32) -> Result<()> {
+            self.ensure_owner()?;
+            self.maturity_weighting_enabled = enabled;
+            self.maturity_blocks = blocks;
+            Ok(())
+        }
+
+        /// Get whether maturity weighting is enabled and its maturity period
+        #[ink(message)]
+        pub fn get_maturity_weighting(&self) -> (bool, u32) {
+            (self.maturity_weighting_enabled, self.maturity_blocks)
+        }
+
+        /// Choose which events the contract emits (owner only)
+        #[ink(message)]
+        pub fn set_event_level(&mut self, level: EventLevel) -> Result<()> {
+            self.ensure_owner()?;
+            self.event_level = level;
+            Ok(())
+        }
+
+        /// Get the current event level
+        #[ink(message)]
+        pub fn get_event_level(&self) -> EventLevel {
+            self.event_level.clone()
+        }
+
+        /// Require quorum to be at least one vote when the snapshot has
+        /// eligible voters (owner only)
+        #[ink(message)]
+        pub fn set_min_quorum_one(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.min_quorum_one = enabled;
+            Ok(())
+        }
+
+        /// Refund deposits of proposals their proposer cancels before any
+        /// votes are cast (owner only)
+        #[ink(message)]
+        pub fn set_refund_clean_cancellations(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.refund_clean_cancellations = enabled;
+            Ok(())
+        }
+
+        /// Set the most voting options a proposal may offer (owner only).
+        /// Proposals always need at least one option, so 0 is rejected.
+        #[ink(message)]
+        pub fn set_max_voting_options(&mut self, max: u32) -> Result<()> {
+            self.ensure_owner()?;
+            if max == 0 {
+                return Err(Error::InvalidVotingOptions);
+            }
+            self.max_voting_options = max;
+            Ok(())
+        }
+
+        /// Get the most voting options a proposal may offer
+        #[ink(message)]
+        pub fn get_max_voting_options(&self) -> u32 {
+            self.max_voting_options
+        }
+
+        /// Set the shortest voting period new proposals may use, in blocks
+        /// (owner only). 0 removes the floor.
+        #[ink(message)]
+        pub fn set_min_voting_period_blocks(&mut self, blocks: u32) -> Result<()> {
+            self.ensure_owner()?;
+            self.min_voting_period_blocks = blocks;
+            Ok(())
+        }
+
+        /// Get the shortest voting period new proposals may use, in blocks
+        #[ink(message)]
+        pub fn get_min_voting_period_blocks(&self) -> u32 {
+            self.min_voting_period_blocks
+        }
+
+        /// Set the fewest eligible voters required to create a proposal
+        /// (owner only). Below it, `create_proposal` fails with
+        /// `InsufficientElectorate` rather than opening a proposal that an
+        /// empty or one-person electorate could pass. 0 removes the floor.
+        #[ink(message)]
+        pub fn set_min_electorate(&mut self, voters: u32) -> Result<()> {
+            self.ensure_owner()?;
+            self.min_electorate = voters;
+            Ok(())
+        }
+
+        /// Get the fewest eligible voters required to create a proposal
+        #[ink(message)]
+        pub fn get_min_electorate(&self) -> u32 {
+            self.min_electorate
+        }
+
+        /// Set the voting weight required to create proposals (owner only)
+        #[ink(message)]
+        pub fn set_proposal_threshold(&mut self, threshold: u128) -> Result<()> {
+            self.ensure_owner()?;
+            self.proposal_threshold = threshold;
+            Ok(())
+        }
+
+        /// Get the voting weight required to create proposals
+        #[ink(message)]
+        pub fn get_proposal_threshold(&self) -> u128 {
+            self.proposal_threshold
+        }
+
+        /// Cap how many proposals one account may ever create, 0 for
+        /// unlimited (owner only)
+        #[ink(message)]
+        pub fn set_max_proposals_per_proposer(&mut self, max: u32) -> Result<()> {
+            self.ensure_owner()?;
+            self.max_proposals_per_proposer = max;
+            Ok(())
+        }
+
+        /// Get how many proposals an account has created
+        #[ink(message)]
+        pub fn get_proposals_created(&self, proposer: H160) -> u32 {
+            self.proposals_created.get(proposer).unwrap_or(0)
+        }
+
+        /// Record lead changes on proposals as votes come in (owner only)
+        #[ink(message)]
+        pub fn set_track_lead_changes(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.track_lead_changes = enabled;
+            Ok(())
+        }
+
+        /// Set the balance treasury payouts must leave behind (owner only)
+        #[ink(message)]
+        pub fn set_treasury_reserve(&mut self, reserve: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.treasury_reserve = reserve;
+            Ok(())
+        }
+
+        /// Get the balance treasury payouts must leave behind
+        #[ink(message)]
+        pub fn get_treasury_reserve(&self) -> Balance {
+            self.treasury_reserve
+        }
+
+        /// Require quorum to be met without the proposer's own vote (owner only)
+        #[ink(message)]
+        pub fn set_exclude_proposer_from_quorum(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.exclude_proposer_from_quorum = enabled;
+            Ok(())
+        }
+
+        /// Set the parameters used by `create_proposal_with_defaults` (owner only)
+        #[ink(message)]
+        pub fn set_default_governance_params(&mut self, params: GovernanceParameters) -> Result<()> {
+            self.ensure_owner()?;
+            Self::ensure_valid_quorum(&params.quorum_threshold)?;
+            self.default_governance_params = params;
+            Ok(())
+        }
+
+        /// Get the parameters used by `create_proposal_with_defaults`
+        #[ink(message)]
+        pub fn get_default_governance_params(&self) -> GovernanceParameters {
+            self.default_governance_params.clone()
+        }
+
+        /// Set or clear the weakest quorum threshold proposals of one type
+        /// may use (owner only)
+        #[ink(message)]
+        pub fn set_type_min_quorum(&mut self, proposal_type: ProposalType, minimum: Option<QuorumThreshold>) -> Result<()> {
+            self.ensure_owner()?;
+            if let Some(minimum) = minimum {
+                Self::ensure_valid_quorum(&minimum)?;
+                self.type_min_quorums.insert(proposal_type, &minimum);
+            } else {
+                self.type_min_quorums.remove(proposal_type);
+            }
+            Ok(())
+        }
+
+        /// Set the credits a voter can spend on quadratic proposals (owner only)
+        #[ink(message)]
+        pub fn set_credits(&mut self, voter: H160, credits: u128) -> Result<()> {
+            self.ensure_owner()?;
+            self.credits.insert(voter, &credits);
+            Ok(())
+        }
+
+        /// Get the credits a voter has left for quadratic proposals
+        #[ink(message)]
+        pub fn get_credits(&self, voter: H160) -> u128 {
+            self.credits.get(voter).unwrap_or(0)
+        }
+
+        /// Set or clear the quorum threshold used for every proposal of one
+        /// type (owner only). An override takes precedence over the threshold
+        /// a proposal was created with, including for proposals still open.
+        #[ink(message)]
+        pub fn set_type_quorum_override(&mut self, proposal_type: ProposalType, threshold: Option<QuorumThreshold>) -> Result<()> {
+            self.ensure_owner()?;
+            if let Some(threshold) = threshold {
+                Self::ensure_valid_quorum(&threshold)?;
+                self.type_quorum_overrides.insert(proposal_type, &threshold);
+            } else {
+                self.type_quorum_overrides.remove(proposal_type);
+            }
+            Ok(())
+        }
+
+        /// Get the quorum threshold a proposal is measured against: its type's
+        /// override if set, otherwise the proposal's own threshold
+        #[ink(message)]
+        pub fn get_quorum_threshold(&self, proposal_id: u32) -> Result<QuorumThreshold> {
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+            Ok(self.quorum_threshold(&proposal))
+        }
+
+        /// Set the global proposal deposit (owner only)
+        #[ink(message)]
+        pub fn set_proposal_deposit(&mut self, amount: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.proposal_deposit = amount;
+            Ok(())
+        }
+
+        /// Set or clear the deposit for one proposal type (owner only)
+        #[ink(message)]
+        pub fn set_type_deposit(&mut self, proposal_type: ProposalType, amount: Option<Balance>) -> Result<()> {
+            self.ensure_owner()?;
+            if let Some(amount) = amount {
+                self.type_deposits.insert(proposal_type, &amount);
+            } else {
+                self.type_deposits.remove(proposal_type);
+            }
+            Ok(())
+        }
+
+        /// Get the deposit required to create a proposal of the given type:
+        /// the per-type deposit if set, otherwise the global deposit
+        #[ink(message)]
+        pub fn get_proposal_cost(&self, proposal_type: ProposalType) -> Balance {
+            self.type_deposits
+                .get(proposal_type)
+                .unwrap_or(self.proposal_deposit)
+        }
+
+        /// Nominate a new owner (owner only). The nominee must call
+        /// `accept_ownership` before the transfer takes effect.
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: H160) -> Result<()> {
+            self.ensure_owner()?;
+            self.pending_owner = Some(new_owner);
+            Ok(())
+        }
+
+        /// Accept a pending ownership nomination
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<()> {
+            self.ensure_not_executing()?;
+            let caller = self.env().caller();
+            if self.pending_owner != Some(caller) {
+                return Err(Error::NotAuthorized);
+            }
+
+            let previous_owner = self.owner;
+            self.owner = caller;
+            self.pending_owner = None;
+
+            if self.emits(EventLevel::Critical) {
+                self.env().emit_event(OwnershipTransferred {
+                    previous_owner,
+                    new_owner: caller,
+                });
+            }
+            Ok(())
+        }
+
+        /// Get the contract owner
+        #[ink(message)]
+        pub fn get_owner(&self) -> H160 {
+            self.owner
+        }
+
+        /// Get the account nominated as the next owner, if any
+        #[ink(message)]
+        pub fn get_pending_owner(&self) -> Option<H160> {
+            self.pending_owner
+        }
+
+        /// Halt or resume proposal creation, voting and execution (pauser only).
+        /// Queries and status updates keep working while paused.
+        #[ink(message)]
+        pub fn set_paused(&mut self, paused: bool) -> Result<()> {
+            self.ensure_not_executing()?;
+            if !self.has_role(self.env().caller(), Role::Pauser) {
+                return Err(Error::NotAuthorized);
+            }
+            self.paused = paused;
+            Ok(())
+        }
+
+        /// Check if the contract is paused
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        /// Set or clear the contract notified after each execution (owner only).
+        /// The hook must expose an `on_proposal_executed(proposal_id: u32)`
+        /// message. With `require_success`, a failing hook call reverts the
+        /// execution; otherwise failures are ignored.
+        #[ink(message)]
+        pub fn set_execution_hook(&mut self, hook: Option<H160>, require_success: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.execution_hook = hook;
+            self.require_hook_success = require_success;
+            Ok(())
+        }
+
+        /// Get the execution hook, if any
+        #[ink(message)]
+        pub fn get_execution_hook(&self) -> Option<H160> {
+            self.execution_hook
+        }
+
+        /// Set the guardian allowed to veto passed proposals (owner only)
+        #[ink(message)]
+        pub fn set_guardian(&mut self, guardian: H160) -> Result<()> {
+            self.ensure_owner()?;
+            self.guardian = Some(guardian);
+            Ok(())
+        }
+
+        /// Get the current guardian, if any
+        #[ink(message)]
+        pub fn get_guardian(&self) -> Option<H160> {
+            self.guardian
+        }
+
+        /// Get a page of the veto log (limit capped at 100)
+        #[ink(message)]
+        pub fn get_veto_log(&self, start: u32, limit: u32) -> Vec<(u32, H160)> {
+            let end = start.saturating_add(limit.min(100)).min(self.veto_count);
+            (start..end)
+                .filter_map(|position| self.veto_log.get(position))
+                .collect()
+        }
+
+        /// Enable or disable early resolution on every vote (owner only)
+        #[ink(message)]
+        pub fn set_resolve_on_vote(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.resolve_on_vote = enabled;
+            Ok(())
+        }
+
+        /// Check if early resolution on vote is enabled
+        #[ink(message)]
+        pub fn get_resolve_on_vote(&self) -> bool {
+            self.resolve_on_vote
+        }
+
+        /// Check if an account is blacklisted
+        #[ink(message)]
+        pub fn is_blacklisted(&self, account: H160) -> bool {
+            self.blacklisted.get(account).is_some()
+        }
+
+        /// Get the components of a proposal's quorum denominator:
+        /// (registered at snapshot, blacklisted excluded, total weight)
+        #[ink(message)]
+        pub fn get_quorum_denominator_breakdown(&self, proposal_id: u32) -> Result<(u32, u32, u128)> {
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+            Ok(Self::quorum_denominator(&proposal))
+        }
+
+        /// Ensure `voter` is registered and not blacklisted
+        fn ensure_active_voter(&self, voter: H160) -> Result<()> {
+            if self.registered_voters.get(voter).is_none() {
+                return Err(Error::NotAuthorized);
+            }
+            if self.blacklisted.get(voter).is_some() {
+                return Err(Error::VoterBlacklisted);
+            }
+            Ok(())
+        }
+
+        /// Run every check a vote by `voter` must pass and return the proposal
+        fn validate_vote(
+            &self,
+            voter: H160,
+            proposal_id: u32,
+            selections: &[u32],
+            credits: Option<u128>,
+        ) -> Result<Proposal> {
+            self.ensure_not_paused()?;
+            let current_block = self.env().block_number();
+            self.ensure_active_voter(voter)?;
+
+            // Get proposal
+            let proposal = self.proposals.get(proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+
+            // Check if proposal is active
+            if proposal.status != ProposalStatus::Active {
+                return Err(Error::ProposalNotActive);
+            }
+
+            // Check if voting period has ended
+            if current_block > proposal.voting_end {
+                return Err(Error::VotingPeriodEnded);
+            }
+
+            // Check if the voter is on the proposal's allowlist, if it has one
+            if !self.is_eligible_for(proposal_id, voter) {
+                return Err(Error::NotEligibleForProposal);
+            }
+
+            // Check if proposers may vote on their own proposals
+            if !self.allow_proposer_vote && voter == proposal.proposer {
+                return Err(Error::ProposerCannotVote);
+            }
+
+            // Check if the voter has already voted, directly or through a delegate
+            if self.votes.get((proposal_id, voter)).is_some()
+                || self.carried_votes.contains((proposal_id, voter))
+            {
+                return Err(Error::AlreadyVoted);
+            }
+
+            // Delegated weight is cast by the delegate, except on quadratic
+            // proposals where everyone spends their own credits
+            let quadratic = proposal.governance_params.voting_mode == VotingMode::Quadratic;
+            if !quadratic && self.delegations.contains(voter) {
+                return Err(Error::VoteDelegated);
+            }
+
+            // Validate option indices
+            for (position, &index) in selections.iter().enumerate() {
+                if index as usize >= proposal.voting_options.options.len()
+                    || selections[..position].contains(&index)
+                {
+                    return Err(Error::InvalidOptionIndex);
+                }
+            }
+
+            // Quadratic proposals take credits, every other mode takes weight
+            match credits {
+                Some(_) if !quadratic => return Err(Error::InvalidVotingMode),
+                None if quadratic => return Err(Error::InvalidVotingMode),
+                Some(spent) if spent > self.credits.get(voter).unwrap_or(0) => {
+                    return Err(Error::InsufficientCredits);
+                }
+                _ => {}
+            }
+
+            Ok(proposal)
+        }
+
+        /// Record a vote for the selected options, or an abstention when none
+        /// are selected. `credits` is the amount spent on a quadratic proposal.
+        /// `lock_blocks` commits the vote for that long in exchange for a
+        /// conviction multiplier.
+        fn cast_vote(
+            &mut self,
+            proposal_id: u32,
+            selections: Vec<u32>,
+            credits: Option<u128>,
+            lock_blocks: u32,
+        ) -> Result<()> {
+            self.ensure_not_executing()?;
+            let caller = self.env().caller();
+            let current_block = self.env().block_number();
+            let mut proposal = self.validate_vote(caller, proposal_id, &selections, credits)?;
+
+            let mut carried = Vec::new();
+            let (base_weight, weight) = if let Some(spent) = credits {
+                // Quadratic: spent credits buy their square root in votes,
+                // while quorum counts the voter's own weight
+                let balance = self.credits.get(caller).unwrap_or(0);
+                self.credits.insert(caller, &balance.saturating_sub(spent));
+                let own = self.matured_weight(caller, self.vote_weight(caller, &proposal.proposal_type));
+                (own, Self::isqrt(spent))
+            } else {
+                // The caller carries the delegated weight that has not voted yet
+                let (weight, delegators) = self.proposal_weight(caller, &proposal);
+                for &delegator in &delegators {
+                    self.carried_votes.insert((proposal_id, delegator), &caller);
+                }
+                carried = delegators;
+                (weight, weight)
+            };
+            // Conviction scales the tally only; quorum counts the base weight
+            let weight = weight.saturating_mul(Self::conviction_multiplier(lock_blocks));
+            let previous_leader = Self::leading_option(&proposal);
+
+            // Update vote counts with overflow protection
+            let ranked = proposal.governance_params.voting_mode == VotingMode::RankedChoice;
+            let choice = match selections.first() {
+                Some(&index) => {
+                    // Ranked ballots count toward their first preference only
+                    let counted = if ranked { &selections[..1] } else { &selections[..] };
+                    for &selected in counted {
+                        let option_idx = selected as usize;
+                        proposal.vote_counts[option_idx] = proposal.vote_counts[option_idx].saturating_add(weight);
+                    }
+                    VoteChoice {
+                        option_index: index,
+                        option_text: proposal.voting_options.options[index as usize].clone(),
+                    }
+                }
+                None => {
+                    proposal.abstain_count = proposal.abstain_count.saturating_add(weight);
+                    VoteChoice {
+                        option_index: ABSTAIN_INDEX,
+                        option_text: String::from("Abstain"),
+                    }
+                }
+            };
+            proposal.total_voters = proposal.total_voters.saturating_add(1);
+            proposal.participating_weight = proposal.participating_weight.saturating_add(base_weight);
+            self.record_lead_change(&proposal, previous_leader);
+
+            // Create and store vote record
+            let vote = Vote {
+                voter: caller,
+                choice,
+                timestamp: current_block,
+                weight,
+                base_weight,
+                selections: if proposal.governance_params.voting_mode == VotingMode::Approval || ranked {
+                    selections
+                } else {
+                    Vec::new()
+                },
+                lock_until: current_block.saturating_add(lock_blocks),
+                carried,
+                credits_spent: credits.unwrap_or(0),
+            };
+            self.votes.insert((proposal_id, caller), &vote);
+            if lock_blocks > 0 {
+                let mut locks = self.conviction_locks.get(caller).unwrap_or_default();
+                locks.retain(|&locked| self.lock_end(locked, caller) > current_block);
+                locks.push(proposal_id);
+                self.conviction_locks.insert(caller, &locks);
+            }
+            let mut voters = self.proposal_voters.get(proposal_id).unwrap_or_default();
+            voters.push(caller);
+            self.proposal_voters.insert(proposal_id, &voters);
+            let mut history = self.voter_history.get(caller).unwrap_or_default();
+            history.push(proposal_id);
+            self.voter_history.insert(caller, &history);
+
+            // Track participation on proposals created after registration
+            if proposal_id >= self.first_eligible_proposal.get(caller).unwrap_or(u32::MAX) {
+                let cast = self.eligible_votes_cast.get(caller).unwrap_or(0);
+                self.eligible_votes_cast.insert(caller, &cast.saturating_add(1));
+            }
+
+            // Resolve immediately if the outcome can no longer change
+            if self.resolve_on_vote && self.try_early_resolution(&mut proposal) {
+                self.finalize_proposal(&proposal);
+            }
+
+            // Update proposal
+            self.proposals.insert(proposal_id, &proposal);
+            self.last_activity_block = current_block;
+
+            if self.emits(EventLevel::All) {
+                self.env().emit_event(VoteCast {
+                    proposal_id,
+                    voter: caller,
+                    option_index: vote.choice.option_index,
+                    weight,
+                });
+            }
+
+            Ok(())
+        }
+
+        /// Weight a vote carries on a proposal of the given type: the type
+        /// specific weight if set, otherwise the general voting weight
+        fn vote_weight(&self, voter: H160, proposal_type: &ProposalType) -> u128 {
+            self.type_weights
+                .get((voter, proposal_type.clone()))
+                .unwrap_or_else(|| self.get_voter_weight(voter))
+        }
+
+        /// Power a voter contributes to `total_voting_power`: their weight if
+        /// registered and not blacklisted, otherwise 0
+        fn counted_power(&self, voter: H160) -> u128 {
+            if self.blacklisted.get(voter).is_some() {
+                return 0;
+            }
+            self.get_voter_weight(voter)
+        }
+
+        /// Weight `voter` would cast on a proposal, following the order
+        /// documented on `compute_effective_weight`, together with the
+        /// delegators whose weight it includes
+        fn proposal_weight(&self, voter: H160, proposal: &Proposal) -> (u128, Vec<H160>) {
+            let carried: Vec<H160> = self.delegated_accounts(voter, self.max_delegation_depth, Some(proposal.id))
+                .into_iter()
+                .filter(|&delegator| self.is_eligible_for(proposal.id, delegator))
+                .collect();
+            let own = self.matured_weight(voter, self.vote_weight(voter, &proposal.proposal_type));
+            let weight = carried
+                .iter()
+                .fold(own, |total, &delegator| {
+                    total.saturating_add(self.matured_weight(delegator, self.counted_power(delegator)))
+                });
+            (weight, carried)
+        }
+
+        /// Whether an account may vote on a proposal: always, unless the
+        /// proposal has an allowlist that leaves the account out
+        fn is_eligible_for(&self, proposal_id: u32, voter: H160) -> bool {
+            !self.proposal_allowlists.contains(proposal_id)
+                || self.eligible_members.contains((proposal_id, voter))
+        }
+
+        /// Weight multiplier for a conviction lock: one plus each whole
+        /// `CONVICTION_PERIOD` locked, capped at `MAX_CONVICTION_MULTIPLIER`
+        fn conviction_multiplier(lock_blocks: u32) -> u128 {
+            u128::from(lock_blocks / CONVICTION_PERIOD)
+                .saturating_add(1)
+                .min(MAX_CONVICTION_MULTIPLIER)
+        }
+
+        /// Integer square root, rounded down
+        fn isqrt(n: u128) -> u128 {
+            if n < 2 {
+                return n;
+            }
+            // Newton's method from an estimate at or above the root
+            let mut x = n;
+            let mut y = x / 2 + 1;
+            while y < x {
+                x = y;
+                y = (x + n / x) / 2;
+            }
+            x
+        }
+
+        /// Scale a voter's weight by maturity: 50% at registration, rising
+        /// linearly to 100% once `maturity_blocks` have passed
+        fn matured_weight(&self, voter: H160, weight: u128) -> u128 {
+            if !self.maturity_weighting_enabled || self.maturity_blocks == 0 {
+                return weight;
+            }
+            let registered = self.registered_at.get(voter).unwrap_or(0);
+            let age = self.env().block_number().saturating_sub(registered);
+            if age >= self.maturity_blocks {
+                return weight;
+            }
+            let maturity = u128::from(self.maturity_blocks);
+            weight
+                .saturating_mul(maturity.saturating_add(u128::from(age)))
+                .checked_div(maturity.saturating_mul(2))
+                .unwrap_or(weight)
+        }
+
+        /// Weight delegated to an account from up to `depth` hops away
+        fn delegated_weight(&self, account: H160, depth: u32) -> u128 {
+            self.delegated_accounts(account, depth, None)
+                .into_iter()
+                .fold(0u128, |total, delegator| total.saturating_add(self.counted_power(delegator)))
+        }
+
+        /// Accounts whose weight flows to `account` from up to `depth` hops
+        /// away. With a proposal, delegators whose weight was already used on
+        /// it (and everyone behind them) are left out. Cost grows with the
+        /// number of delegators within that depth.
+        fn delegated_accounts(&self, account: H160, depth: u32, proposal_id: Option<u32>) -> Vec<H160> {
+            let mut accounts = Vec::new();
+            if depth == 0 {
+                return accounts;
+            }
+
+            for delegator in self.delegators.get(account).unwrap_or_default() {
+                if let Some(proposal_id) = proposal_id {
+                    if self.votes.contains((proposal_id, delegator))
+                        || self.carried_votes.contains((proposal_id, delegator))
+                    {
+                        continue;
+                    }
+                }
+                accounts.push(delegator);
+                accounts.extend(self.delegated_accounts(delegator, depth.saturating_sub(1), proposal_id));
+            }
+            accounts
+        }
+
+        /// Drop a voter's delegation, if any, from both indexes
+        fn remove_delegation(&mut self, voter: H160) {
+            let Some(previous) = self.delegations.get(voter) else {
+                return;
+            };
+            self.delegations.remove(voter);
+            let mut delegators = self.delegators.get(previous).unwrap_or_default();
+            delegators.retain(|&delegator| delegator != voter);
+            if delegators.is_empty() {
+                self.delegators.remove(previous);
+            } else {
+                self.delegators.insert(previous, &delegators);
+            }
+        }
+
+        /// Add an unregistered account to the voter set
+        fn add_voter(&mut self, voter: H160) {
+            self.registered_voters.insert(voter, &true);
+            self.total_voters = self.total_voters.saturating_add(1);
+            self.total_voting_power = self.total_voting_power.saturating_add(self.counted_power(voter));
+            self.first_eligible_proposal.insert(voter, &self.next_proposal_id);
+
+            // Block numbers only grow, so `registration_blocks` stays sorted
+            let block = self.env().block_number();
+            self.registered_at.insert(voter, &block);
+            let last = self.registration_block_count
+                .checked_sub(1)
+                .and_then(|position| self.registration_blocks.get(position));
+            if last != Some(block) {
+                self.registration_blocks.insert(self.registration_block_count, &block);
+                self.registration_block_count = self.registration_block_count.saturating_add(1);
+            }
+            let count = self.registration_counts.get(block).unwrap_or(0);
+            self.registration_counts.insert(block, &count.saturating_add(1));
+
+            if self.emits(EventLevel::All) {
+                self.env().emit_event(VoterRegistered { voter });
+            }
+        }
+
+        /// Set a voter's weight and update the running total, rejecting
+        /// abrupt shifts when smoothing is enabled and reductions while the
+        /// voter is conviction-locked
+        fn apply_voting_power(&mut self, voter: H160, power: u128) -> Result<()> {
+            if power < self.get_voter_weight(voter) {
+                self.ensure_not_conviction_locked(voter)?;
+            }
+            if self.smooth_weight_changes {
+                let current = self.voting_power.get(voter).unwrap_or(1);
+                if current.abs_diff(power) > self.max_weight_delta {
+                    return Err(Error::WeightChangeTooLarge);
+                }
+            }
+
+            let before = self.counted_power(voter);
+            self.voting_power.insert(voter, &power);
+            self.sync_total_power(voter, before);
+            Ok(())
+        }
+
+        /// Apply a change in a voter's counted power to the running total
+        fn sync_total_power(&mut self, voter: H160, before: u128) {
+            let after = self.counted_power(voter);
+            self.total_voting_power = self.total_voting_power
+                .saturating_sub(before)
+                .saturating_add(after);
+        }
+
+        /// Whether an event of the given level fires under the configured level
+        fn emits(&self, level: EventLevel) -> bool {
+            match self.event_level {
+                EventLevel::Off => false,
+                EventLevel::Critical => level == EventLevel::Critical,
+                EventLevel::All => true,
+            }
+        }
+
+        /// Whether an account may execute proposals
+        fn can_execute(&self, account: H160) -> bool {
+            !self.restrict_execution || self.has_role(account, Role::Executor)
+        }
+
+        /// Whether `account` could execute a proposal right now: the contract
+        /// is not paused, the account may execute, and the proposal is the
+        /// passed head of the queue, past its delay, inside its window and
+        /// with its dependency, if any, executed
+        fn is_executable_by(&self, proposal: &Proposal, account: H160) -> bool {
+            let current_block = self.env().block_number();
+            let dependency_executed = proposal.depends_on.is_none_or(|dependency| {
+                self.proposals
+                    .get(dependency)
+                    .is_some_and(|dependency| dependency.status == ProposalStatus::Executed)
+            });
+            !self.paused
+                && self.can_execute(account)
+                && proposal.status == ProposalStatus::Passed
+                && self.execution_queue.first() == Some(&proposal.id)
+                && dependency_executed
+                && current_block >= proposal.execution_time
+                && !Self::execution_window_closed(proposal, current_block)
+        }
+
+        /// Call `on_proposal_executed` on the hook contract. Returns whether
+        /// the call succeeded.
+        fn notify_execution_hook(&self, hook: H160, proposal_id: u32) -> bool {
+            use ink::env::call::{build_call, ExecutionInput, Selector};
+
+            let result = build_call::<ink::env::DefaultEnvironment>()
+                .call(hook)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("on_proposal_executed")))
+                        .push_arg(proposal_id),
+                )
+                .returns::<()>()
+                .try_invoke();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Status change `update_proposal_status` makes at `current_block`,
+        /// with the rejection reason, if any
+        fn pending_transition(
+            &self,
+            proposal: &Proposal,
+            current_block: u32,
+        ) -> Option<(ProposalStatus, Option<RejectionReason>)> {
+            // Expire passed proposals whose execution window has closed
+            if proposal.status == ProposalStatus::Passed {
+                return Self::execution_window_closed(proposal, current_block)
+                    .then_some((ProposalStatus::Expired, None));
+            }
+
+            // Only update if proposal is still active
+            if proposal.status != ProposalStatus::Active {
+                return None;
+            }
+
+            // Before the end of voting, only a supermajority or an
+            // unreachable quorum resolves early
+            if current_block <= proposal.voting_end {
+                if self.has_insurmountable_supermajority(proposal) {
+                    return Some((ProposalStatus::Passed, None));
+                }
+                if !self.quorum_reachable(proposal) {
+                    return Some((ProposalStatus::Rejected, Some(RejectionReason::QuorumUnreachable)));
+                }
+                return None;
+            }
+
+            // Nobody voted at all: expire rather than reject
+            if proposal.total_voters == 0 {
+                return Some((ProposalStatus::Expired, None));
+            }
+
+            // Check if quorum is reached
+            if !self.quorum_met(proposal) {
+                return Some((ProposalStatus::Rejected, Some(RejectionReason::QuorumNotReached)));
+            }
+
+            // Optionally reject when abstentions outnumber actual votes
+            let total_votes = Self::option_votes(proposal);
+            if self.abstain_majority_rejects && proposal.abstain_count > total_votes {
+                return Some((ProposalStatus::Rejected, Some(RejectionReason::AbstainMajority)));
+            }
+
+            // Quorum made up only of abstentions leaves no option to carry
+            if total_votes == 0 {
+                return Some((ProposalStatus::Rejected, Some(RejectionReason::NoOptionVotes)));
+            }
+
+            // Approval proposals need option 0 to lead and clear the fraction
+            if proposal.governance_params.min_approval_bps > 0 {
+                return if self.approval_met(proposal) {
+                    Some((ProposalStatus::Passed, None))
+                } else {
+                    Some((ProposalStatus::Rejected, Some(RejectionReason::ApprovalThresholdNotMet)))
+                };
+            }
+
+            // A tie is two or more options sharing the actual maximum, or an
+            // instant runoff without a winner on ranked-choice proposals
+            let (leader, runner_up) = Self::leading_tallies(proposal);
+            let tie = if proposal.governance_params.voting_mode == VotingMode::RankedChoice {
+                self.tally_ranked_choice(proposal).is_none()
+            } else {
+                leader == runner_up
+            };
+
+            if tie {
+                Some((ProposalStatus::Rejected, Some(RejectionReason::Tie)))
+            } else {
+                Some((ProposalStatus::Passed, None))
+            }
+        }
+
+        /// Apply the transition `pending_transition` reports, if any, and
+        /// return whether the status changed
+        fn apply_pending_transition(&mut self, mut proposal: Proposal, current_block: u32) -> bool {
+            let Some((status, rejection_reason)) = self.pending_transition(&proposal, current_block) else {
+                return false;
+            };
+            let was_passed = proposal.status == ProposalStatus::Passed;
+            proposal.status = status;
+            if rejection_reason.is_some() {
+                proposal.rejection_reason = rejection_reason;
+            }
+
+            // Expiring a passed proposal keeps its frozen results
+            if was_passed {
+                self.proposals.insert(proposal.id, &proposal);
+                self.record_status_change(&proposal, ProposalStatus::Passed);
+                self.remove_from_execution_queue(proposal.id);
+                self.settle_dependents(proposal.id);
+            } else {
+                self.finalize_proposal(&proposal);
+            }
+            true
+        }
+
+        /// Stop holding a cancelled proposal's deposit for refund; it stays
+        /// in the treasury as spendable funds
+        fn forfeit_deposit(&mut self, proposal_id: u32) {
+            if let Some((_, amount)) = self.proposal_deposits.get(proposal_id) {
+                self.total_held_deposits = self.total_held_deposits.saturating_sub(amount);
+            }
+        }
+
+        /// Move a proposal from `previous` to its current status in the
+        /// per-type status counts
+        fn record_status_change(&mut self, proposal: &Proposal, previous: ProposalStatus) {
+            let previous_key = (proposal.proposal_type.clone(), previous);
+            let count = self.status_counts.get(&previous_key).unwrap_or(0);
+            self.status_counts.insert(&previous_key, &count.saturating_sub(1));
+            let current_key = (proposal.proposal_type.clone(), proposal.status.clone());
+            let count = self.status_counts.get(&current_key).unwrap_or(0);
+            self.status_counts.insert(&current_key, &count.saturating_add(1));
+        }
+
+        /// Store a proposal that has just left Active and cache its results
+        fn finalize_proposal(&mut self, proposal: &Proposal) {
+            self.proposals.insert(proposal.id, proposal);
+            self.record_status_change(proposal, ProposalStatus::Active);
+            self.cached_results.insert(proposal.id, &self.compute_results(proposal));
+            if proposal.status == ProposalStatus::Passed {
+                self.enqueue_for_execution(proposal);
+            } else {
+                self.settle_dependents(proposal.id);
+            }
+        }
+
+        /// Append a passed proposal to the back of the execution queue. A
+        /// dependent proposal only joins once its dependency has executed,
+        /// so it can never hold up the proposals queued behind it.
+        fn enqueue_for_execution(&mut self, proposal: &Proposal) {
+            if let Some(dependency) = proposal.depends_on {
+                let executed = self.proposals.get(dependency)
+                    .is_some_and(|dependency| dependency.status == ProposalStatus::Executed);
+                if !executed {
+                    return;
+                }
+            }
+            if !self.execution_queue.contains(&proposal.id) {
+                self.execution_queue.push(proposal.id);
+            }
+        }
+
+        /// Follow up on the dependents of a proposal that reached a final
+        /// status. Passed dependents of an executed proposal join the queue;
+        /// if it ended any other way they can never run and expire, along
+        /// with their own dependents.
+        fn settle_dependents(&mut self, proposal_id: u32) {
+            let Some(dependency) = self.proposals.get(proposal_id) else {
+                return;
+            };
+            let executed = dependency.status == ProposalStatus::Executed;
+            for dependent_id in self.dependents.get(proposal_id).unwrap_or_default() {
+                let Some(mut dependent) = self.proposals.get(dependent_id) else {
+                    continue;
+                };
+                match dependent.status {
+                    ProposalStatus::Passed if executed => self.enqueue_for_execution(&dependent),
+                    ProposalStatus::Active | ProposalStatus::Passed if !executed => {
+                        let was_active = dependent.status == ProposalStatus::Active;
+                        dependent.status = ProposalStatus::Expired;
+                        if was_active {
+                            self.finalize_proposal(&dependent);
+                        } else {
+                            self.proposals.insert(dependent_id, &dependent);
+                            self.record_status_change(&dependent, ProposalStatus::Passed);
+                            self.settle_dependents(dependent_id);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        /// Drop a proposal that can no longer be executed from the queue
+        fn remove_from_execution_queue(&mut self, proposal_id: u32) {
+            self.execution_queue.retain(|&id| id != proposal_id);
+        }
+
+        /// Invoke a proposal's execution call. Returns whether the target is a
+        /// contract and the call succeeded; any return value is ignored. The
+        /// target may call back into the contract, so the reentrancy guard
+        /// is what stops it executing proposals again.
+        fn invoke_execution_call(&self, call: &ExecutionCall) -> bool {
+            use ink::env::call::{build_call, ExecutionInput, Selector};
+
+            if self.env().code_hash(&call.target).is_err() {
+                return false;
+            }
+            let result = build_call::<ink::env::DefaultEnvironment>()
+                .call(call.target)
+                .ref_time_limit(call.gas_limit)
+                .call_flags(ink::env::CallFlags::ALLOW_REENTRY)
+                .exec_input(ExecutionInput::new(Selector::new(call.selector)).push_arg(RawInput(&call.input)))
+                .returns::<()>()
+                .try_invoke();
+            matches!(result, Ok(Ok(())))
+        }
+
+        /// Ensure the treasury can cover a payout without touching funds held
+        /// for voters and proposers, and keep the operating reserve
+        fn ensure_payout_covered(&self, action: &TreasuryAction) -> Result<()> {
+            let amount = U256::from(action.amount);
+            let balance = U256::from(self.get_spendable_balance());
+            if balance < amount {
+                return Err(Error::TransferFailed);
+            }
+            if balance - amount < U256::from(self.treasury_reserve) {
+                return Err(Error::ReserveViolation);
+            }
+            Ok(())
+        }
+
+        /// Whether a passed proposal is certain to fail execution at
+        /// `current_block`: its window has closed, the treasury cannot cover
+        /// its payout, or its dependency ended without executing
+        fn can_never_execute(&self, proposal: &Proposal, current_block: u32) -> bool {
+            let dependency_failed = proposal.depends_on.is_some_and(|dependency| {
+                self.proposals.get(dependency).is_none_or(|dependency| {
+                    !matches!(
+                        dependency.status,
+                        ProposalStatus::Active | ProposalStatus::Passed | ProposalStatus::Executed
+                    )
+                })
+            });
+            Self::execution_window_closed(proposal, current_block)
+                || proposal.treasury_action.as_ref().is_some_and(|action| self.ensure_payout_covered(action).is_err())
+                || dependency_failed
+        }
+
+        /// Ensure no proposal is executing. The execution call may call back
+        /// into the contract, and the outer `execute_proposal` writes the
+        /// contract's fields back when it returns, dropping whatever a nested
+        /// call changed; so every message that writes state refuses to run.
+        fn ensure_not_executing(&self) -> Result<()> {
+            if self.executing.get().unwrap_or(false) {
+                return Err(Error::ReentrancyDetected);
+            }
+            Ok(())
+        }
+
+        /// Ensure `voter` has no conviction lock still running. Until it ends
+        /// the voter cannot withdraw, deregister, delegate or lose weight.
+        /// Locks on proposals that failed no longer count.
+        fn ensure_not_conviction_locked(&self, voter: H160) -> Result<()> {
+            let current_block = self.env().block_number();
+            let locked = self.conviction_locks
+                .get(voter)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|&proposal_id| self.lock_end(proposal_id, voter) > current_block)
+                .filter_map(|proposal_id| self.proposals.get(proposal_id))
+                .any(|proposal| !matches!(
+                    proposal.status,
+                    ProposalStatus::Cancelled
+                        | ProposalStatus::Rejected
+                        | ProposalStatus::Vetoed
+                        | ProposalStatus::Expired
+                ));
+            if locked {
+                return Err(Error::VoteLocked);
+            }
+            Ok(())
+        }
+
+        /// Block at which `voter`'s vote on a proposal stops being locked, 0
+        /// without a vote
+        fn lock_end(&self, proposal_id: u32, voter: H160) -> u32 {
+            self.votes.get((proposal_id, voter)).map_or(0, |vote| vote.lock_until)
+        }
+
+        /// Ensure a quorum threshold lies between 1% and 100%
+        fn ensure_valid_quorum(threshold: &QuorumThreshold) -> Result<()> {
+            if !(1..=100).contains(&threshold.to_percentage()) {
+                return Err(Error::InvalidProposal);
+            }
+            Ok(())
+        }
+
+        /// Ensure the contract is not paused
+        fn ensure_not_paused(&self) -> Result<()> {
+            if self.paused {
+                return Err(Error::ContractPaused);
+            }
+            Ok(())
+        }
+
+        /// Ensure the caller may grant and revoke the given role
+        fn ensure_role_manager(&self, role: Role) -> Result<()> {
+            self.ensure_not_executing()?;
+            let caller = self.env().caller();
+            if caller == self.owner {
+                return Ok(());
+            }
+            if role == Role::Admin || !self.has_role(caller, Role::Admin) {
+                return Err(Error::NotAuthorized);
+            }
+            Ok(())
+        }
+
+        /// Ensure the caller is the contract owner
+        fn ensure_owner(&self) -> Result<()> {
+            self.ensure_not_executing()?;
+            if self.env().caller() != self.owner {
+                return Err(Error::NotAuthorized);
+            }
+            Ok(())
+        }
+
+        /// Quorum denominator components, frozen at proposal creation
+        fn quorum_denominator(proposal: &Proposal) -> (u32, u32, u128) {
+            (proposal.snapshot_voters, proposal.snapshot_blacklisted, proposal.snapshot_total_power)
+        }
+
+        /// Quorum threshold in force for a proposal, following the precedence
+        /// documented on `get_quorum_threshold`
+        fn quorum_threshold(&self, proposal: &Proposal) -> QuorumThreshold {
+            self.type_quorum_overrides
+                .get(proposal.proposal_type.clone())
+                .unwrap_or_else(|| proposal.governance_params.quorum_threshold.clone())
+        }
+
+        /// Votes required for a proposal to reach quorum
+        fn quorum_required(&self, proposal: &Proposal) -> u128 {
+            let (voters, blacklisted, total_weight) = Self::quorum_denominator(proposal);
+            let quorum_percentage = self.quorum_threshold(proposal).to_percentage();
+            let required = total_weight
+                .saturating_mul(quorum_percentage as u128)
+                .saturating_div(100);
+
+            // Keep small registries from rounding the quorum down to nothing
+            if self.min_quorum_one && voters > blacklisted {
+                return required.max(1);
+            }
+            required
+        }
+
+        /// Weight that participated in a proposal, abstentions included.
+        /// Each voter counts once, even when approving several options.
+        fn participation(proposal: &Proposal) -> u128 {
+            proposal.participating_weight
+        }
+
+        /// Participation counted toward quorum: all of it, or without the
+        /// proposer's own vote when `exclude_proposer_from_quorum` is set
+        fn quorum_participation(&self, proposal: &Proposal) -> u128 {
+            let participation = Self::participation(proposal);
+            if !self.exclude_proposer_from_quorum {
+                return participation;
+            }
+            let own = self.votes
+                .get((proposal.id, proposal.proposer))
+                .map_or(0, |vote| vote.base_weight);
+            participation.saturating_sub(own)
+        }
+
+        /// Remove a vote's weight from the tallies of the options it selected
+        fn withdraw_vote_weight(proposal: &mut Proposal, vote: &Vote) {
+            if vote.choice.option_index == ABSTAIN_INDEX {
+                proposal.abstain_count = proposal.abstain_count.saturating_sub(vote.weight);
+            } else if vote.selections.is_empty()
+                || proposal.governance_params.voting_mode == VotingMode::RankedChoice
+            {
+                let old_idx = vote.choice.option_index as usize;
+                proposal.vote_counts[old_idx] = proposal.vote_counts[old_idx].saturating_sub(vote.weight);
+            } else {
+                for &selected in &vote.selections {
+                    let old_idx = selected as usize;
+                    proposal.vote_counts[old_idx] = proposal.vote_counts[old_idx].saturating_sub(vote.weight);
+                }
+            }
+        }
+
+        /// Whether participation meets both the percentage quorum and the
+        /// proposal's minimum absolute weight
+        fn quorum_met(&self, proposal: &Proposal) -> bool {
+            let participation = self.quorum_participation(proposal);
+            participation >= self.quorum_required(proposal)
+                && participation >= proposal.governance_params.min_absolute_weight
+        }
+
+        /// Whether quorum can still be met if all weight that has not voted
+        /// yet turns out. Outstanding weight is the larger of the snapshot and
+        /// the current total (just the snapshot on allowlisted proposals),
+        /// minus what has voted. Neither total reflects type-specific
+        /// weights, so types that have them count as always reachable.
+        fn quorum_reachable(&self, proposal: &Proposal) -> bool {
+            if self.has_type_weights(&proposal.proposal_type) {
+                return true;
+            }
+            let (_, _, snapshot_weight) = Self::quorum_denominator(proposal);
+            let outstanding = if self.proposal_allowlists.contains(proposal.id) {
+                snapshot_weight
+            } else {
+                self.total_voting_power.max(snapshot_weight)
+            };
+            let remaining = outstanding.saturating_sub(Self::participation(proposal));
+            let possible = self.quorum_participation(proposal).saturating_add(remaining);
+            possible >= self.quorum_required(proposal)
+                && possible >= proposal.governance_params.min_absolute_weight
+        }
+
+        /// Mark an active proposal as passed if quorum is met and the leading
+        /// option can no longer be caught by the votes still outstanding.
+        /// Returns whether the proposal was resolved.
+        fn try_early_resolution(&self, proposal: &mut Proposal) -> bool {
+            if proposal.status != ProposalStatus::Active {
+                return false;
+            }
+
+            if !self.quorum_met(proposal) || !self.may_resolve_early(proposal) {
+                return false;
+            }
+
+            let (leader, runner_up) = Self::leading_tallies(proposal);
+
+            // Tally weight eligible voters could still add, each at the
+            // highest conviction
+            let remaining = Self::outstanding_tally(self.outstanding_power(proposal));
+
+            // Abstentions could still outweigh the option votes
+            if self.abstain_majority_possible(proposal, remaining) {
+                return false;
+            }
+
+            // Instant runoff can overturn a plurality, so ranked-choice
+            // proposals need a majority of all weight that could vote
+            if proposal.governance_params.voting_mode == VotingMode::RankedChoice {
+                let possible = Self::tallied_weight(proposal).saturating_add(remaining);
+                if leader.saturating_mul(2) <= possible {
+                    return false;
+                }
+            }
+
+            if leader > runner_up.saturating_add(remaining) {
+                proposal.status = ProposalStatus::Passed;
+                return true;
+            }
+            false
+        }
+
+        /// Index of the option holding the highest tally alone, if any
+        fn leading_option(proposal: &Proposal) -> Option<u32> {
+            let (leader, runner_up) = Self::leading_tallies(proposal);
+            if leader == 0 || leader == runner_up {
+                return None;
+            }
+            proposal.vote_counts
+                .iter()
+                .position(|&votes| votes == leader)
+                .and_then(|index| u32::try_from(index).ok())
+        }
+
+        /// Index of the winning option: the instant-runoff winner on
+        /// ranked-choice proposals, otherwise the sole leader
+        fn winning_option(&self, proposal: &Proposal) -> Option<u32> {
+            if proposal.governance_params.voting_mode == VotingMode::RankedChoice {
+                return self.tally_ranked_choice(proposal);
+            }
+            Self::leading_option(proposal)
+        }
+
+        /// Run an instant runoff over a proposal's ranked ballots.
+        ///
+        /// Each round, every ballot counts its weight toward its highest
+        /// ranked option still in the race. An option holding more than half
+        /// of the counted weight wins. Otherwise the option with the least
+        /// weight is eliminated; among options tied for least, the one with
+        /// the highest index goes first. Ballots whose ranked options have all
+        /// been eliminated are exhausted and leave the count, so the majority
+        /// is of the ballots still live. Returns `None` when no ballots are
+        /// live, or when every remaining option holds the same weight.
+        ///
+        /// Reads every vote once per round, so the cost grows with voters
+        /// times options.
+        fn tally_ranked_choice(&self, proposal: &Proposal) -> Option<u32> {
+            let ballots: Vec<Vote> = self.proposal_voters
+                .get(proposal.id)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|voter| self.votes.get((proposal.id, voter)))
+                .filter(|vote| !vote.selections.is_empty())
+                .collect();
+            let mut remaining: Vec<u32> = (0..proposal.voting_options.options.len() as u32).collect();
+
+            loop {
+                let mut counts = ink::prelude::vec![0u128; proposal.voting_options.options.len()];
+                let mut live: u128 = 0;
+                for ballot in &ballots {
+                    if let Some(&choice) = ballot.selections.iter().find(|index| remaining.contains(index)) {
+                        counts[choice as usize] = counts[choice as usize].saturating_add(ballot.weight);
+                        live = live.saturating_add(ballot.weight);
+                    }
+                }
+                if live == 0 {
+                    return None;
+                }
+
+                let mut leader = remaining[0];
+                let mut trailer = remaining[0];
+                for &index in &remaining {
+                    if counts[index as usize] > counts[leader as usize] {
+                        leader = index;
+                    }
+                    if counts[index as usize] <= counts[trailer as usize] {
+                        trailer = index;
+                    }
+                }
+                if counts[leader as usize].saturating_mul(2) > live {
+                    return Some(leader);
+                }
+                if counts[leader as usize] == counts[trailer as usize] {
+                    return None;
+                }
+                remaining.retain(|&index| index != trailer);
+            }
+        }
+
+        /// Append a lead change to the proposal's history when tracking is
+        /// enabled and the leader differs from `previous_leader`. Keeps at
+        /// most 50 entries per proposal.
+        fn record_lead_change(&mut self, proposal: &Proposal, previous_leader: Option<u32>) {
+            if !self.track_lead_changes {
+                return;
+            }
+
+            let Some(leader) = Self::leading_option(proposal) else {
+                return;
+            };
+            if previous_leader == Some(leader) {
+                return;
+            }
+
+            let mut changes = self.lead_changes.get(proposal.id).unwrap_or_default();
+            if changes.len() < 50 {
+                changes.push((self.env().block_number(), leader));
+                self.lead_changes.insert(proposal.id, &changes);
+            }
+        }
+
+        /// Highest and second highest option tallies
+        fn leading_tallies(proposal: &Proposal) -> (u128, u128) {
+            let mut leader = 0u128;
+            let mut runner_up = 0u128;
+            for &votes in proposal.vote_counts.iter() {
+                if votes > leader {
+                    runner_up = leader;
+                    leader = votes;
+                } else if votes > runner_up {
+                    runner_up = votes;
+                }
+            }
+            (leader, runner_up)
+        }
+
+        /// Whether the leading option holds more than two thirds of the tally
+        /// weight that could still be reached and the runner-up could not
+        /// catch it even if all outstanding power went its way at the
+        /// highest conviction
+        fn has_insurmountable_supermajority(&self, proposal: &Proposal) -> bool {
+            if !self.quorum_met(proposal) || !self.may_resolve_early(proposal) {
+                return false;
+            }
+
+            let (leader, runner_up) = Self::leading_tallies(proposal);
+            let remaining = Self::outstanding_tally(self.outstanding_power(proposal));
+            let possible = Self::tallied_weight(proposal).saturating_add(remaining);
+            if leader.saturating_mul(3) <= possible.saturating_mul(2)
+                || self.abstain_majority_possible(proposal, remaining)
+            {
+                return false;
+            }
+
+            leader > runner_up.saturating_add(remaining)
+        }
+
+        /// Whether `abstain_majority_rejects` could still reject a proposal
+        /// for its abstentions if `remaining` tally weight abstained too
+        fn abstain_majority_possible(&self, proposal: &Proposal, remaining: u128) -> bool {
+            let total_votes = Self::option_votes(proposal);
+            self.abstain_majority_rejects
+                && proposal.abstain_count.saturating_add(remaining) > total_votes
+        }
+
+        /// Whether a proposal's tally can be compared with outstanding power.
+        /// Approval fractions are only judged once voting ends, and quadratic
+        /// tallies are bought with credits rather than power, so neither
+        /// resolves early. Nor do types with type-specific weights, which the
+        /// power totals do not reflect.
+        fn may_resolve_early(&self, proposal: &Proposal) -> bool {
+            proposal.governance_params.min_approval_bps == 0
+                && proposal.governance_params.voting_mode != VotingMode::Quadratic
+                && !self.has_type_weights(&proposal.proposal_type)
+        }
+
+        /// Whether any voter has a type-specific weight for `proposal_type`
+        fn has_type_weights(&self, proposal_type: &ProposalType) -> bool {
+            self.type_weighted_voters.get(proposal_type).unwrap_or(0) > 0
+        }
+
+        /// Power that could still be cast on a proposal: the current total
+        /// less the power of accounts whose weight was used on it, directly
+        /// or carried, and never less than the snapshot power not yet cast.
+        /// Voters who left, were blacklisted or lost weight after voting thus
+        /// do not shrink it. Reads every vote on the proposal.
+        fn outstanding_power(&self, proposal: &Proposal) -> u128 {
+            let used = self.proposal_voters
+                .get(proposal.id)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|voter| self.votes.get((proposal.id, voter)))
+                .fold(0u128, |total, vote| {
+                    vote.carried.iter().fold(
+                        total.saturating_add(self.counted_power(vote.voter)),
+                        |total, &delegator| total.saturating_add(self.counted_power(delegator)),
+                    )
+                });
+            let (_, _, snapshot_weight) = Self::quorum_denominator(proposal);
+            self.total_voting_power
+                .saturating_sub(used)
+                .max(snapshot_weight.saturating_sub(Self::participation(proposal)))
+        }
+
+        /// Most tally weight `power` not yet cast could add, if all of it
+        /// voted at the highest conviction
+        fn outstanding_tally(power: u128) -> u128 {
+            power.saturating_mul(MAX_CONVICTION_MULTIPLIER)
+        }
+
+        /// Tally weight cast so far: option tallies plus abstentions.
+        /// Approval ballots count once per selected option.
+        fn tallied_weight(proposal: &Proposal) -> u128 {
+            Self::option_votes(proposal).saturating_add(proposal.abstain_count)
+        }
+
+        /// Weight cast on options, abstentions excluded. Saturates, since
+        /// each option's count may already be near `u128::MAX`.
+        fn option_votes(proposal: &Proposal) -> u128 {
+            proposal.vote_counts.iter().fold(0, |total, &votes| total.saturating_add(votes))
+        }
+
+        /// Whether option 0 leads outright with more than `min_approval_bps`
+        /// of the tallied weight, abstentions included
+        fn approval_met(&self, proposal: &Proposal) -> bool {
+            let approvals = proposal.vote_counts.first().copied().unwrap_or(0);
+            let required = Self::tallied_weight(proposal)
+                .saturating_mul(u128::from(proposal.governance_params.min_approval_bps));
+            Self::leading_option(proposal) == Some(0) && approvals.saturating_mul(10_000) > required
+        }
+
+        /// Whether the execution window of a proposal has passed
+        fn execution_window_closed(proposal: &Proposal, current_block: u32) -> bool {
+            let window = proposal.governance_params.execution_window_blocks;
+            window > 0 && current_block > proposal.execution_time.saturating_add(window)
         }
     }
 
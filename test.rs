@@ -2,6 +2,62 @@
 mod tests {
     use crate::treasury_governance::*;
 
+    fn default_governance_params() -> GovernanceParameters {
+        GovernanceParameters {
+            voting_period: VotingPeriod::SevenDays,
+            quorum_threshold: QuorumThreshold::Ten,
+            execution_delay: ExecutionDelay::OneDay,
+            execution_window_blocks: 0,
+            min_absolute_weight: 0,
+            allow_payload_amendment: false,
+            voting_mode: VotingMode::Linear,
+            min_approval_bps: 0,
+        }
+    }
+
+    fn yes_no_options() -> VotingOptions {
+        VotingOptions {
+            options: vec!["Yes".to_string(), "No".to_string()],
+        }
+    }
+
+    /// Move the test environment to the given block
+    fn set_block(block: u32) {
+        ink::env::test::set_block_number::<ink::env::DefaultEnvironment>(block);
+    }
+
+    /// Move to the first block after a proposal's voting period
+    fn advance_to_voting_end(contract: &TreasuryGovernance, proposal_id: u32) {
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        set_block(proposal.voting_end + 1);
+    }
+
+    /// Move to the first block at which a proposal may be executed
+    fn advance_to_execution_time(contract: &TreasuryGovernance, proposal_id: u32) {
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        set_block(proposal.execution_time);
+    }
+
+    /// Create a Yes/No proposal with the given parameters as the current caller
+    fn create_test_proposal(
+        contract: &mut TreasuryGovernance,
+        proposal_type: ProposalType,
+        governance_params: GovernanceParameters,
+    ) -> u32 {
+        contract.create_proposal(
+            "Test Proposal".to_string(),
+            "Test Description".to_string(),
+            proposal_type,
+            governance_params,
+            yes_no_options(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        ).unwrap()
+    }
+
     #[ink::test]
     fn new_works() {
         let contract = TreasuryGovernance::new();
@@ -23,6 +79,17 @@ mod tests {
         assert!(contract.is_registered_voter(accounts.alice));
     }
 
+    #[ink::test]
+    fn double_registration_is_rejected() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        contract.register_voter().unwrap();
+        assert_eq!(contract.register_voter(), Err(Error::AlreadyRegistered));
+        assert_eq!(contract.get_total_voters(), 1);
+    }
+
     #[ink::test]
     fn proposal_creation_works() {
         let mut contract = TreasuryGovernance::new();
@@ -39,6 +106,11 @@ mod tests {
             voting_period: VotingPeriod::SevenDays,
             quorum_threshold: QuorumThreshold::Ten,
             execution_delay: ExecutionDelay::OneDay,
+            execution_window_blocks: 0,
+            min_absolute_weight: 0,
+            allow_payload_amendment: false,
+            voting_mode: VotingMode::Linear,
+            min_approval_bps: 0,
         };
 
         let voting_options = VotingOptions {
@@ -51,6 +123,11 @@ mod tests {
             ProposalType::Treasury,
             governance_params,
             voting_options,
+            None,
+            None,
+            None,
+            None,
+            None,
         );
 
         assert!(result.is_ok());
@@ -74,6 +151,11 @@ mod tests {
             voting_period: VotingPeriod::SevenDays,
             quorum_threshold: QuorumThreshold::Ten,
             execution_delay: ExecutionDelay::OneDay,
+            execution_window_blocks: 0,
+            min_absolute_weight: 0,
+            allow_payload_amendment: false,
+            voting_mode: VotingMode::Linear,
+            min_approval_bps: 0,
         };
 
         let voting_options = VotingOptions {
@@ -86,6 +168,11 @@ mod tests {
             ProposalType::Treasury,
             governance_params,
             voting_options,
+            None,
+            None,
+            None,
+            None,
+            None,
         ).unwrap();
 
         // Vote on proposal
@@ -113,6 +200,11 @@ mod tests {
             voting_period: VotingPeriod::SevenDays,
             quorum_threshold: QuorumThreshold::Ten,
             execution_delay: ExecutionDelay::OneDay,
+            execution_window_blocks: 0,
+            min_absolute_weight: 0,
+            allow_payload_amendment: false,
+            voting_mode: VotingMode::Linear,
+            min_approval_bps: 0,
         };
 
         let voting_options = VotingOptions {
@@ -125,6 +217,11 @@ mod tests {
             ProposalType::Treasury,
             governance_params,
             voting_options,
+            None,
+            None,
+            None,
+            None,
+            None,
         ).unwrap();
 
         // Vote once
@@ -149,6 +246,11 @@ mod tests {
             voting_period: VotingPeriod::SevenDays,
             quorum_threshold: QuorumThreshold::Ten,
             execution_delay: ExecutionDelay::OneDay,
+            execution_window_blocks: 0,
+            min_absolute_weight: 0,
+            allow_payload_amendment: false,
+            voting_mode: VotingMode::Linear,
+            min_approval_bps: 0,
         };
 
         // Test empty voting options
@@ -162,6 +264,11 @@ mod tests {
             ProposalType::Treasury,
             governance_params.clone(),
             empty_options,
+            None,
+            None,
+            None,
+            None,
+            None,
         );
 
         assert_eq!(result, Err(Error::InvalidVotingOptions));
@@ -177,6 +284,11 @@ mod tests {
             ProposalType::Treasury,
             governance_params,
             too_many_options,
+            None,
+            None,
+            None,
+            None,
+            None,
         );
 
         assert_eq!(result, Err(Error::InvalidVotingOptions));
@@ -198,6 +310,11 @@ mod tests {
             voting_period: VotingPeriod::SevenDays,
             quorum_threshold: QuorumThreshold::Ten,
             execution_delay: ExecutionDelay::OneDay,
+            execution_window_blocks: 0,
+            min_absolute_weight: 0,
+            allow_payload_amendment: false,
+            voting_mode: VotingMode::Linear,
+            min_approval_bps: 0,
         };
 
         let voting_options = VotingOptions {
@@ -210,6 +327,11 @@ mod tests {
             ProposalType::Treasury,
             governance_params,
             voting_options,
+            None,
+            None,
+            None,
+            None,
+            None,
         ).unwrap();
 
         // Vote on proposal
@@ -225,6 +347,10 @@ mod tests {
         let mut contract = TreasuryGovernance::new();
         let accounts = ink::env::test::default_accounts();
         
+        // Register a second voter so a single vote is no supermajority
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+
         // Set the caller to alice before registering
         ink::env::test::set_caller(accounts.alice);
         
@@ -236,6 +362,11 @@ mod tests {
             voting_period: VotingPeriod::ThreeDays,
             quorum_threshold: QuorumThreshold::Ten,
             execution_delay: ExecutionDelay::Immediately,
+            execution_window_blocks: 0,
+            min_absolute_weight: 0,
+            allow_payload_amendment: false,
+            voting_mode: VotingMode::Linear,
+            min_approval_bps: 0,
         };
 
         let voting_options = VotingOptions {
@@ -248,6 +379,11 @@ mod tests {
             ProposalType::Treasury,
             governance_params,
             voting_options,
+            None,
+            None,
+            None,
+            None,
+            None,
         ).unwrap();
 
         // Vote on proposal
@@ -264,11 +400,11 @@ mod tests {
 
     #[ink::test]
     fn proposal_execution() {
-        let mut contract = TreasuryGovernance::new();
         let accounts = ink::env::test::default_accounts();
         
-        // Set the caller to alice before registering
+        // Set the caller to alice before deploying and registering
         ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
         
         // Register voter
         contract.register_voter().unwrap();
@@ -278,6 +414,11 @@ mod tests {
             voting_period: VotingPeriod::ThreeDays,
             quorum_threshold: QuorumThreshold::Ten,
             execution_delay: ExecutionDelay::Immediately,
+            execution_window_blocks: 0,
+            min_absolute_weight: 0,
+            allow_payload_amendment: false,
+            voting_mode: VotingMode::Linear,
+            min_approval_bps: 0,
         };
 
         let voting_options = VotingOptions {
@@ -290,6 +431,11 @@ mod tests {
             ProposalType::Treasury,
             governance_params,
             voting_options,
+            None,
+            None,
+            None,
+            None,
+            None,
         ).unwrap();
 
         // Vote on proposal
@@ -320,6 +466,11 @@ mod tests {
             voting_period: VotingPeriod::SevenDays,
             quorum_threshold: QuorumThreshold::Ten,
             execution_delay: ExecutionDelay::OneDay,
+            execution_window_blocks: 0,
+            min_absolute_weight: 0,
+            allow_payload_amendment: false,
+            voting_mode: VotingMode::Linear,
+            min_approval_bps: 0,
         };
 
         let voting_options = VotingOptions {
@@ -332,6 +483,11 @@ mod tests {
             ProposalType::Governance,
             governance_params,
             voting_options,
+            None,
+            None,
+            None,
+            None,
+            None,
         ).unwrap();
 
         // Vote on proposal
@@ -371,4 +527,5452 @@ mod tests {
         assert_eq!(stats.executed_proposals, 0);
         assert_eq!(stats.total_voters, 1);
     }
-}
\ No newline at end of file
+
+    #[ink::test]
+    fn execution_within_window_works() {
+        let accounts = ink::env::test::default_accounts();
+
+        // Set the caller to alice before deploying and registering
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        // Register voter
+        contract.register_voter().unwrap();
+
+        // Create proposal with a 100 block execution window
+        let governance_params = GovernanceParameters {
+            voting_period: VotingPeriod::ThreeDays,
+            quorum_threshold: QuorumThreshold::Ten,
+            execution_delay: ExecutionDelay::Immediately,
+            execution_window_blocks: 100,
+            min_absolute_weight: 0,
+            allow_payload_amendment: false,
+            voting_mode: VotingMode::Linear,
+            min_approval_bps: 0,
+        };
+
+        let voting_options = VotingOptions {
+            options: vec!["Yes".to_string(), "No".to_string()],
+        };
+
+        let proposal_id = contract.create_proposal(
+            "Test Proposal".to_string(),
+            "Test Description".to_string(),
+            ProposalType::Treasury,
+            governance_params,
+            voting_options,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ).unwrap();
+
+        assert!(contract.vote(proposal_id, 0).is_ok());
+
+        // Move past the voting period and finalize
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        set_block(proposal.voting_end + 1);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+
+        // Execute inside the window
+        set_block(proposal.execution_time + 100);
+        assert!(contract.execute_proposal(proposal_id).is_ok());
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Executed);
+    }
+
+    #[ink::test]
+    fn execution_after_window_rejected_and_expired() {
+        let accounts = ink::env::test::default_accounts();
+
+        // Set the caller to alice before deploying and registering
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        // Register voter
+        contract.register_voter().unwrap();
+
+        // Create proposal with a 100 block execution window
+        let governance_params = GovernanceParameters {
+            voting_period: VotingPeriod::ThreeDays,
+            quorum_threshold: QuorumThreshold::Ten,
+            execution_delay: ExecutionDelay::OneDay,
+            execution_window_blocks: 100,
+            min_absolute_weight: 0,
+            allow_payload_amendment: false,
+            voting_mode: VotingMode::Linear,
+            min_approval_bps: 0,
+        };
+
+        let voting_options = VotingOptions {
+            options: vec!["Yes".to_string(), "No".to_string()],
+        };
+
+        let proposal_id = contract.create_proposal(
+            "Test Proposal".to_string(),
+            "Test Description".to_string(),
+            ProposalType::Treasury,
+            governance_params,
+            voting_options,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ).unwrap();
+
+        assert!(contract.vote(proposal_id, 0).is_ok());
+
+        // Move past the voting period and finalize
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        set_block(proposal.voting_end + 1);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+
+        // Attempt execution after the window has closed
+        set_block(proposal.execution_time + 101);
+        assert_eq!(contract.execute_proposal(proposal_id), Err(Error::ExecutionWindowClosed));
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+
+        // Finalizing now marks the stale mandate as expired
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Expired);
+        assert_eq!(contract.execute_proposal(proposal_id), Err(Error::ProposalNotReadyForExecution));
+    }
+
+    #[ink::test]
+    fn quorum_denominator_breakdown_works() {
+        let mut contract = TreasuryGovernance::new();
+        let accounts = ink::env::test::default_accounts();
+
+        // Register three voters
+        for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        ink::env::test::set_caller(accounts.alice);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        assert_eq!(contract.get_quorum_denominator_breakdown(proposal_id), Ok((3, 0, 3)));
+        assert_eq!(
+            contract.get_quorum_denominator_breakdown(99),
+            Err(Error::ProposalNotFound)
+        );
+    }
+
+    #[ink::test]
+    fn blacklisting_changes_quorum_denominator() {
+        let accounts = ink::env::test::default_accounts();
+
+        // Alice deploys, so she is the owner
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        // Only the owner may blacklist
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.set_blacklisted(accounts.charlie, true), Err(Error::NotAuthorized));
+
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(contract.set_blacklisted(accounts.django, true), Err(Error::VoterNotRegistered));
+        assert!(contract.set_blacklisted(accounts.charlie, true).is_ok());
+        assert!(contract.is_blacklisted(accounts.charlie));
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        assert_eq!(contract.get_quorum_denominator_breakdown(proposal_id), Ok((3, 1, 2)));
+
+        // Blacklisted voters cannot vote
+        ink::env::test::set_caller(accounts.charlie);
+        assert_eq!(contract.vote(proposal_id, 0), Err(Error::VoterBlacklisted));
+
+        // Later changes don't move the snapshot of an existing proposal
+        ink::env::test::set_caller(accounts.alice);
+        assert!(contract.set_blacklisted(accounts.charlie, false).is_ok());
+        assert!(!contract.is_blacklisted(accounts.charlie));
+        assert_eq!(contract.get_quorum_denominator_breakdown(proposal_id), Ok((3, 1, 2)));
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        assert_eq!(contract.get_quorum_denominator_breakdown(proposal_id), Ok((3, 0, 3)));
+    }
+
+    #[ink::test]
+    fn resolve_on_vote_passes_decisive_vote() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        // Only the owner may toggle the flag
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.set_resolve_on_vote(true), Err(Error::NotAuthorized));
+
+        ink::env::test::set_caller(accounts.alice);
+        assert!(contract.set_resolve_on_vote(true).is_ok());
+        assert!(contract.get_resolve_on_vote());
+
+        contract.set_voting_power(accounts.bob, 10).unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        // First vote: the outstanding voters could still overturn the result
+        assert!(contract.vote(proposal_id, 0).is_ok());
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Active);
+
+        // Second vote makes the lead insurmountable, even against charlie
+        // voting at the highest conviction
+        ink::env::test::set_caller(accounts.bob);
+        assert!(contract.vote(proposal_id, 0).is_ok());
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+
+        // Voting is closed once resolved
+        ink::env::test::set_caller(accounts.charlie);
+        assert_eq!(contract.vote(proposal_id, 1), Err(Error::ProposalNotActive));
+    }
+
+    #[ink::test]
+    fn resolve_on_vote_counts_voters_who_left_after_voting() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([10, 5, 5]);
+        contract.set_resolve_on_vote(true).unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        // Alice's vote keeps counting after she leaves the registry
+        contract.vote(proposal_id, 0).unwrap();
+        contract.deregister_voter().unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 1).unwrap();
+
+        // Charlie can still tie the result, so it stays open
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Active);
+        assert_eq!(contract.get_effective_status(proposal_id), Ok(ProposalStatus::Active));
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 1).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![10, 10]);
+    }
+
+    #[ink::test]
+    fn resolve_on_vote_disabled_by_default() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        ink::env::test::set_caller(accounts.alice);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        for voter in [accounts.alice, accounts.bob] {
+            ink::env::test::set_caller(voter);
+            assert!(contract.vote(proposal_id, 0).is_ok());
+        }
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Active);
+    }
+
+    #[ink::test]
+    fn get_voter_weight_works() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        // Registered voter without an explicit weight
+        assert_eq!(contract.get_voter_weight(accounts.alice), 1);
+
+        // Only the owner may set weights
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.set_voting_power(accounts.bob, 10), Err(Error::NotAuthorized));
+
+        // Explicitly weighted voter
+        ink::env::test::set_caller(accounts.alice);
+        assert!(contract.set_voting_power(accounts.bob, 10).is_ok());
+        assert_eq!(contract.get_voter_weight(accounts.bob), 10);
+
+        // Unregistered account, even with a weight on record
+        assert!(contract.set_voting_power(accounts.charlie, 5).is_ok());
+        assert_eq!(contract.get_voter_weight(accounts.charlie), 0);
+        assert_eq!(contract.get_voter_weight(accounts.django), 0);
+    }
+
+    #[ink::test]
+    fn deregister_cancels_proposals_when_enabled() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        assert!(contract.set_cancel_proposals_on_deregister(true).is_ok());
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+        let first = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        let second = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+
+        // A proposal from someone else stays untouched
+        ink::env::test::set_caller(accounts.charlie);
+        contract.register_voter().unwrap();
+        let other = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        ink::env::test::set_caller(accounts.bob);
+        assert!(contract.deregister_voter().is_ok());
+
+        assert_eq!(contract.get_proposal(first).unwrap().status, ProposalStatus::Cancelled);
+        assert_eq!(contract.get_proposal(second).unwrap().status, ProposalStatus::Cancelled);
+        assert_eq!(contract.get_proposal(other).unwrap().status, ProposalStatus::Active);
+        assert_eq!(contract.get_stats().active_proposals, 1);
+    }
+
+    #[ink::test]
+    fn deregister_leaves_proposals_by_default() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        // Only the owner may change the policy
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.set_cancel_proposals_on_deregister(true), Err(Error::NotAuthorized));
+
+        contract.register_voter().unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        assert!(contract.deregister_voter().is_ok());
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Active);
+    }
+
+    #[ink::test]
+    fn participation_rate_works() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        // Created before Bob registered, so not counted for him
+        let before = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.get_participation_rate(accounts.bob), 0);
+        contract.register_voter().unwrap();
+        assert_eq!(contract.get_participation_rate(accounts.bob), 0);
+
+        ink::env::test::set_caller(accounts.alice);
+        let ids: Vec<u32> = (0..4)
+            .map(|_| create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params()))
+            .collect();
+
+        // Bob votes on the older proposal and half of his eligible ones
+        ink::env::test::set_caller(accounts.bob);
+        assert!(contract.vote(before, 0).is_ok());
+        assert!(contract.vote(ids[0], 0).is_ok());
+        assert!(contract.vote(ids[1], 1).is_ok());
+
+        assert_eq!(contract.get_participation_rate(accounts.bob), 50);
+        assert_eq!(contract.get_participation_rate(accounts.alice), 0);
+    }
+
+    #[ink::test]
+    fn min_absolute_weight_rejects_low_participation() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        // 10% of 3 voters is met by a single vote, but 2 weight is required
+        ink::env::test::set_caller(accounts.alice);
+        let governance_params = GovernanceParameters {
+            min_absolute_weight: 2,
+            allow_payload_amendment: false,
+            voting_mode: VotingMode::Linear,
+            min_approval_bps: 0,
+            ..default_governance_params()
+        };
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, governance_params);
+        assert!(contract.vote(proposal_id, 0).is_ok());
+
+        let results = contract.get_proposal_results(proposal_id).unwrap();
+        assert!(results.total_votes >= results.quorum_required);
+        assert!(!results.quorum_reached);
+        assert_eq!(contract.has_reached_quorum(proposal_id), Ok(false));
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        set_block(proposal.voting_end + 1);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Rejected);
+    }
+
+    #[ink::test]
+    fn min_absolute_weight_met_passes() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        ink::env::test::set_caller(accounts.alice);
+        let governance_params = GovernanceParameters {
+            min_absolute_weight: 2,
+            allow_payload_amendment: false,
+            voting_mode: VotingMode::Linear,
+            min_approval_bps: 0,
+            ..default_governance_params()
+        };
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, governance_params);
+        for voter in [accounts.alice, accounts.bob] {
+            ink::env::test::set_caller(voter);
+            assert!(contract.vote(proposal_id, 0).is_ok());
+        }
+        assert_eq!(contract.has_reached_quorum(proposal_id), Ok(true));
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        set_block(proposal.voting_end + 1);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+    }
+
+    #[ink::test]
+    fn veto_log_records_each_veto() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        // Non-owners cannot appoint a guardian
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.set_guardian(accounts.bob), Err(Error::NotAuthorized));
+
+        ink::env::test::set_caller(accounts.alice);
+        assert!(contract.set_guardian(accounts.bob).is_ok());
+        assert_eq!(contract.get_guardian(), Some(accounts.bob));
+
+        let ids: Vec<u32> = (0..3)
+            .map(|_| {
+                let id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+                contract.vote(id, 0).unwrap();
+                id
+            })
+            .collect();
+
+        // Finalize all proposals inside their execution delay
+        let voting_end = contract.get_proposal(ids[0]).unwrap().voting_end;
+        set_block(voting_end + 1);
+        for &id in &ids {
+            contract.update_proposal_status(id).unwrap();
+            assert_eq!(contract.get_proposal(id).unwrap().status, ProposalStatus::Passed);
+        }
+
+        // Only the guardian may veto
+        assert_eq!(contract.veto_proposal(ids[0]), Err(Error::NotAuthorized));
+
+        ink::env::test::set_caller(accounts.bob);
+        assert!(contract.veto_proposal(ids[0]).is_ok());
+        assert!(contract.veto_proposal(ids[2]).is_ok());
+        assert_eq!(contract.veto_proposal(ids[0]), Err(Error::ProposalNotPassed));
+        assert_eq!(contract.get_proposal(ids[0]).unwrap().status, ProposalStatus::Vetoed);
+        assert_eq!(contract.get_pass_rate_by_type(ProposalType::Treasury), (1, 3));
+
+        // A new guardian's vetoes are attributed to them
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_guardian(accounts.charlie).unwrap();
+        ink::env::test::set_caller(accounts.charlie);
+        assert!(contract.veto_proposal(ids[1]).is_ok());
+
+        assert_eq!(
+            contract.get_veto_log(0, 10),
+            vec![(ids[0], accounts.bob), (ids[2], accounts.bob), (ids[1], accounts.charlie)]
+        );
+        assert_eq!(contract.get_veto_log(1, 1), vec![(ids[2], accounts.bob)]);
+        assert!(contract.get_veto_log(3, 10).is_empty());
+    }
+
+    #[ink::test]
+    fn type_weights_apply_per_proposal_type() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+
+        // Only the owner may set type weights
+        assert_eq!(
+            contract.set_type_weight(accounts.bob, ProposalType::Technical, 5),
+            Err(Error::NotAuthorized)
+        );
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_voting_power(accounts.bob, 2).unwrap();
+        contract.set_type_weight(accounts.bob, ProposalType::Technical, 5).unwrap();
+
+        let technical = create_test_proposal(&mut contract, ProposalType::Technical, default_governance_params());
+        let treasury = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        ink::env::test::set_caller(accounts.bob);
+        assert!(contract.vote(technical, 0).is_ok());
+        assert!(contract.vote(treasury, 0).is_ok());
+
+        assert_eq!(contract.get_user_vote(technical, accounts.bob).unwrap().weight, 5);
+        assert_eq!(contract.get_user_vote(treasury, accounts.bob).unwrap().weight, 2);
+        assert_eq!(contract.get_proposal(technical).unwrap().vote_counts, vec![5, 0]);
+        assert_eq!(contract.get_proposal(treasury).unwrap().vote_counts, vec![2, 0]);
+    }
+
+    #[ink::test]
+    fn last_activity_tracks_governance_actions() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        assert_eq!(contract.get_last_activity(), 0);
+
+        // Creation
+        set_block(10);
+        let governance_params = GovernanceParameters {
+            execution_delay: ExecutionDelay::Immediately,
+            ..default_governance_params()
+        };
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, governance_params);
+        assert_eq!(contract.get_last_activity(), 10);
+
+        // Vote
+        set_block(20);
+        contract.vote(proposal_id, 0).unwrap();
+        assert_eq!(contract.get_last_activity(), 20);
+
+        // Reads and status updates don't count as activity
+        let voting_end = contract.get_proposal(proposal_id).unwrap().voting_end;
+        set_block(voting_end + 1);
+        contract.get_stats();
+        contract.get_proposal_results(proposal_id).unwrap();
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_last_activity(), 20);
+
+        // Execution
+        contract.execute_proposal(proposal_id).unwrap();
+        assert_eq!(contract.get_last_activity(), voting_end + 1);
+    }
+
+    #[ink::test]
+    fn restricted_recipients_must_be_approved() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        // Only the owner manages the recipient policy
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.set_restrict_recipients(true), Err(Error::NotAuthorized));
+        assert_eq!(contract.set_recipient_approved(accounts.bob, true), Err(Error::NotAuthorized));
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_restrict_recipients(true).unwrap();
+        contract.set_recipient_approved(accounts.django, true).unwrap();
+        assert!(contract.is_approved_recipient(accounts.django));
+        assert!(!contract.is_approved_recipient(accounts.eve));
+
+        let payout = |recipient| Some(TreasuryAction { recipient, amount: 100 });
+
+        // Unapproved recipient is rejected
+        let result = contract.create_proposal(
+            "Pay Eve".to_string(),
+            "Test Description".to_string(),
+            ProposalType::Treasury,
+            default_governance_params(),
+            yes_no_options(),
+            payout(accounts.eve),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(result, Err(Error::RecipientNotApproved));
+
+        // Approved recipient is allowed and stored on the proposal
+        let proposal_id = contract.create_proposal(
+            "Pay Django".to_string(),
+            "Test Description".to_string(),
+            ProposalType::Treasury,
+            default_governance_params(),
+            yes_no_options(),
+            payout(accounts.django),
+            None,
+            None,
+            None,
+            None,
+        ).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().treasury_action, payout(accounts.django));
+
+        // Payouts are only valid on treasury proposals
+        let result = contract.create_proposal(
+            "Pay Django".to_string(),
+            "Test Description".to_string(),
+            ProposalType::Governance,
+            default_governance_params(),
+            yes_no_options(),
+            payout(accounts.django),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(result, Err(Error::InvalidProposal));
+
+        // Without the restriction any recipient is accepted
+        contract.set_restrict_recipients(false).unwrap();
+        let result = contract.create_proposal(
+            "Pay Eve".to_string(),
+            "Test Description".to_string(),
+            ProposalType::Treasury,
+            default_governance_params(),
+            yes_no_options(),
+            payout(accounts.eve),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[ink::test]
+    fn lifecycle_active_passed_executed() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        ink::env::test::set_caller(accounts.alice);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        contract.vote(proposal_id, 0).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 1).unwrap();
+
+        // Still active until voting ends
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Active);
+
+        // Passed once voting ends
+        advance_to_voting_end(&contract, proposal_id);
+        assert_eq!(contract.vote(proposal_id, 0), Err(Error::VotingPeriodEnded));
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+
+        // Not executable before the delay
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(contract.execute_proposal(proposal_id), Err(Error::ExecutionDelayNotMet));
+
+        // Executed once the delay elapses
+        advance_to_execution_time(&contract, proposal_id);
+        assert!(contract.execute_proposal(proposal_id).is_ok());
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Executed);
+        assert_eq!(contract.execute_proposal(proposal_id), Err(Error::ProposalNotReadyForExecution));
+
+        let stats = contract.get_stats();
+        assert_eq!(stats.active_proposals, 0);
+        assert_eq!(stats.executed_proposals, 1);
+    }
+
+    #[ink::test]
+    fn lifecycle_active_rejected_without_quorum() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob, accounts.charlie, accounts.django, accounts.eve] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        // 25% of 14 power requires 3; only Bob's single vote comes in
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_voting_power(accounts.alice, 10).unwrap();
+        let governance_params = GovernanceParameters {
+            quorum_threshold: QuorumThreshold::TwentyFive,
+            ..default_governance_params()
+        };
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, governance_params);
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+        assert_eq!(proposal.rejection_reason, Some(RejectionReason::QuorumNotReached));
+
+        advance_to_execution_time(&contract, proposal_id);
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(contract.execute_proposal(proposal_id), Err(Error::ProposalNotReadyForExecution));
+    }
+
+    #[ink::test]
+    fn lifecycle_active_rejected_on_tie() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        ink::env::test::set_caller(accounts.alice);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        contract.vote(proposal_id, 0).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 1).unwrap();
+
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+        assert_eq!(proposal.rejection_reason, Some(RejectionReason::Tie));
+    }
+
+    #[ink::test]
+    fn get_total_votes_works() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_voting_power(accounts.charlie, 4).unwrap();
+        let split = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        let single = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        let unvoted = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        contract.vote(split, 0).unwrap();
+        contract.vote(single, 1).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(split, 1).unwrap();
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(split, 1).unwrap();
+
+        assert_eq!(contract.get_total_votes(split), Ok(6));
+        assert_eq!(contract.get_total_votes(single), Ok(1));
+        assert_eq!(contract.get_total_votes(unvoted), Ok(0));
+        assert_eq!(contract.get_total_votes(99), Err(Error::ProposalNotFound));
+    }
+
+    #[ink::test]
+    fn self_vote_prohibition() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        // Only the owner may change the rule
+        assert_eq!(contract.set_allow_proposer_vote(false), Err(Error::NotAuthorized));
+
+        // Allowed by default
+        let first = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        assert!(contract.vote(first, 0).is_ok());
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_allow_proposer_vote(false).unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        let second = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        assert_eq!(contract.vote(second, 0), Err(Error::ProposerCannotVote));
+        assert_eq!(contract.compute_effective_weight(accounts.bob, second), Ok(0));
+
+        // Other voters are unaffected
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(contract.compute_effective_weight(accounts.alice, second), Ok(1));
+        assert!(contract.vote(second, 0).is_ok());
+    }
+
+    #[ink::test]
+    fn current_block_follows_environment() {
+        let contract = TreasuryGovernance::new();
+        assert_eq!(contract.current_block(), ink::env::block_number::<ink::env::DefaultEnvironment>());
+
+        set_block(42);
+        assert_eq!(contract.current_block(), 42);
+
+        ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+        assert_eq!(contract.current_block(), 43);
+    }
+
+    #[ink::test]
+    fn weight_smoothing_limits_changes() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+        assert_eq!(contract.set_weight_smoothing(true, 5), Err(Error::NotAuthorized));
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_weight_smoothing(true, 5).unwrap();
+
+        // A large jump is rejected and leaves the weight untouched
+        assert_eq!(contract.set_voting_power(accounts.bob, 20), Err(Error::WeightChangeTooLarge));
+        assert_eq!(contract.get_voter_weight(accounts.bob), 1);
+
+        // Within-limit changes succeed and reach the target over several calls
+        assert!(contract.set_voting_power(accounts.bob, 6).is_ok());
+        assert!(contract.set_voting_power(accounts.bob, 11).is_ok());
+        assert!(contract.set_voting_power(accounts.bob, 16).is_ok());
+        assert!(contract.set_voting_power(accounts.bob, 20).is_ok());
+        assert_eq!(contract.get_voter_weight(accounts.bob), 20);
+
+        // Decreases are limited too
+        assert_eq!(contract.set_voting_power(accounts.bob, 0), Err(Error::WeightChangeTooLarge));
+
+        // Disabling smoothing lifts the limit
+        contract.set_weight_smoothing(false, 0).unwrap();
+        assert!(contract.set_voting_power(accounts.bob, 0).is_ok());
+    }
+
+    #[ink::test]
+    fn weight_smoothing_limits_locked_weight() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_deposit_weighting(true).unwrap();
+        contract.set_weight_smoothing(true, 5).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+
+        // Locking a large amount cannot jump past the cap
+        assert_eq!(lock_deposit(&mut contract, 50), Err(Error::WeightChangeTooLarge));
+        assert_eq!(contract.get_voter_weight(accounts.bob), 1);
+        assert_eq!(contract.get_locked_deposit(accounts.bob), 0);
+
+        lock_deposit(&mut contract, 6).unwrap();
+        lock_deposit(&mut contract, 5).unwrap();
+        assert_eq!(contract.get_voter_weight(accounts.bob), 11);
+        assert_eq!(contract.get_locked_deposit(accounts.bob), 11);
+    }
+
+    #[ink::test]
+    fn proposals_filtered_by_status_and_type() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        let treasury_passed = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        let governance_passed = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        contract.vote(treasury_passed, 0).unwrap();
+        contract.vote(governance_passed, 0).unwrap();
+        advance_to_voting_end(&contract, treasury_passed);
+        contract.update_proposal_status(treasury_passed).unwrap();
+        contract.update_proposal_status(governance_passed).unwrap();
+
+        let treasury_active = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        let governance_active = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let technical_active = create_test_proposal(&mut contract, ProposalType::Technical, default_governance_params());
+
+        assert_eq!(
+            contract.get_proposals_filtered(Some(ProposalStatus::Active), Some(ProposalType::Treasury), 0, 10),
+            vec![treasury_active]
+        );
+        assert_eq!(
+            contract.get_proposals_filtered(None, Some(ProposalType::Governance), 0, 10),
+            vec![governance_passed, governance_active]
+        );
+        assert_eq!(
+            contract.get_proposals_filtered(Some(ProposalStatus::Passed), None, 0, 10),
+            vec![treasury_passed, governance_passed]
+        );
+        assert_eq!(
+            contract.get_proposals_filtered(Some(ProposalStatus::Active), None, 1, 1),
+            vec![governance_active]
+        );
+        assert_eq!(contract.get_proposals_filtered(None, None, 0, 100).len(), 5);
+        assert_eq!(
+            contract.get_proposals_filtered(None, Some(ProposalType::Technical), 0, 10),
+            vec![technical_active]
+        );
+        assert!(contract.get_proposals_filtered(Some(ProposalStatus::Executed), None, 0, 10).is_empty());
+    }
+
+    /// Create a passed treasury proposal paying `amount` to Django, still
+    /// inside its execution delay
+    fn passed_treasury_proposal(contract: &mut TreasuryGovernance, allow_payload_amendment: bool) -> u32 {
+        let accounts = ink::env::test::default_accounts();
+        let governance_params = GovernanceParameters {
+            allow_payload_amendment,
+            ..default_governance_params()
+        };
+        let proposal_id = contract.create_proposal(
+            "Pay Django".to_string(),
+            "Test Description".to_string(),
+            ProposalType::Treasury,
+            governance_params,
+            yes_no_options(),
+            Some(TreasuryAction { recipient: accounts.django, amount: 100 }),
+            None,
+            None,
+            None,
+            None,
+        ).unwrap();
+        contract.vote(proposal_id, 0).unwrap();
+        advance_to_voting_end(contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        proposal_id
+    }
+
+    #[ink::test]
+    fn stuck_queue_head_can_be_expired() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        contract.set_guardian(accounts.bob).unwrap();
+
+        // The head pays out more than the treasury holds, so it keeps failing
+        let stuck = passed_treasury_proposal(&mut contract, false);
+        assert_eq!(contract.expire_queue_head(stuck), Err(Error::ExecutionDelayNotMet));
+        let dependent = create_dependent_proposal(&mut contract, stuck).unwrap();
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        let behind = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        contract.vote(behind, 0).unwrap();
+        advance_to_voting_end(&contract, behind);
+        contract.update_proposal_status(behind).unwrap();
+        ink::env::test::set_contract_balance(ink::env::test::callee(), ink::U256::from(50));
+        assert_eq!(contract.execute_proposal(stuck), Err(Error::TransferFailed));
+        assert_eq!(contract.execute_proposal(behind), Err(Error::NotNextInQueue));
+
+        // Only the owner or guardian may expire it, and only at the head
+        ink::env::test::set_caller(accounts.charlie);
+        assert_eq!(contract.expire_queue_head(stuck), Err(Error::NotAuthorized));
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.expire_queue_head(behind), Err(Error::NotNextInQueue));
+        assert_eq!(contract.expire_queue_head(stuck), Ok(()));
+
+        assert_eq!(contract.get_proposal(stuck).unwrap().status, ProposalStatus::Expired);
+        assert_eq!(contract.get_proposal(dependent).unwrap().status, ProposalStatus::Expired);
+        assert_eq!(contract.get_execution_queue(), vec![behind]);
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(contract.execute_proposal(behind), Ok(()));
+    }
+
+    #[ink::test]
+    fn executable_queue_head_expires_only_after_grace_period() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        contract.set_guardian(accounts.bob).unwrap();
+        ink::env::test::set_contract_balance(ink::env::test::callee(), ink::U256::from(1_000));
+
+        // The treasury covers the payout, so nothing stops the head running
+        let head = passed_treasury_proposal(&mut contract, false);
+        advance_to_execution_time(&contract, head);
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.expire_queue_head(head), Err(Error::QueueHeadExecutable));
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(contract.expire_queue_head(head), Err(Error::QueueHeadExecutable));
+
+        let execution_time = contract.get_proposal(head).unwrap().execution_time;
+        set_block(execution_time + QUEUE_EXPIRY_GRACE_PERIOD - 1);
+        assert_eq!(contract.expire_queue_head(head), Err(Error::QueueHeadExecutable));
+        set_block(execution_time + QUEUE_EXPIRY_GRACE_PERIOD);
+        assert_eq!(contract.expire_queue_head(head), Ok(()));
+        assert_eq!(contract.get_proposal(head).unwrap().status, ProposalStatus::Expired);
+        assert!(contract.get_execution_queue().is_empty());
+    }
+
+    #[ink::test]
+    fn queue_head_with_closed_window_expires_at_once() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        let mut params = default_governance_params();
+        params.execution_window_blocks = 10;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        contract.vote(proposal_id, 0).unwrap();
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+
+        advance_to_execution_time(&contract, proposal_id);
+        assert_eq!(contract.expire_queue_head(proposal_id), Err(Error::QueueHeadExecutable));
+        let execution_time = contract.get_proposal(proposal_id).unwrap().execution_time;
+        set_block(execution_time + 11);
+        assert_eq!(contract.expire_queue_head(proposal_id), Ok(()));
+        assert!(contract.get_execution_queue().is_empty());
+    }
+
+    #[ink::test]
+    fn amend_execution_payload_before_execution() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        let proposal_id = passed_treasury_proposal(&mut contract, true);
+        let amended = TreasuryAction { recipient: accounts.eve, amount: 80 };
+
+        // Third parties cannot amend
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.amend_execution_payload(proposal_id, amended.clone()), Err(Error::NotAuthorized));
+
+        // A new recipient must be approved, even without recipient restrictions
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(
+            contract.amend_execution_payload(proposal_id, amended.clone()),
+            Err(Error::RecipientNotApproved)
+        );
+        contract.set_recipient_approved(accounts.eve, true).unwrap();
+        assert!(contract.amend_execution_payload(proposal_id, amended.clone()).is_ok());
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().treasury_action, Some(amended.clone()));
+
+        // Rejected once the execution delay has elapsed
+        advance_to_execution_time(&contract, proposal_id);
+        assert_eq!(
+            contract.amend_execution_payload(proposal_id, amended),
+            Err(Error::AmendmentWindowClosed)
+        );
+    }
+
+    #[ink::test]
+    fn amend_execution_payload_cannot_increase_amount() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        let proposal_id = passed_treasury_proposal(&mut contract, true);
+        let increased = TreasuryAction { recipient: accounts.django, amount: 101 };
+        assert_eq!(
+            contract.amend_execution_payload(proposal_id, increased),
+            Err(Error::AmendmentIncreasesAmount)
+        );
+        let reduced = TreasuryAction { recipient: accounts.django, amount: 60 };
+        contract.amend_execution_payload(proposal_id, reduced.clone()).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().treasury_action, Some(reduced));
+    }
+
+    #[ink::test]
+    fn amend_execution_payload_requires_flag() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        let proposal_id = passed_treasury_proposal(&mut contract, false);
+        assert_eq!(
+            contract.amend_execution_payload(proposal_id, TreasuryAction { recipient: accounts.eve, amount: 80 }),
+            Err(Error::AmendmentNotAllowed)
+        );
+    }
+
+    #[ink::test]
+    fn events_are_emitted() {
+        use scale::Decode;
+
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Technical, default_governance_params());
+        contract.vote(proposal_id, 1).unwrap();
+
+        let events = ink::env::test::recorded_events();
+        assert_eq!(events.len(), 3);
+
+        let registered = VoterRegistered::decode(&mut &events[0].data[..]).unwrap();
+        assert_eq!(registered, VoterRegistered { voter: accounts.bob });
+
+        let created = ProposalCreated::decode(&mut &events[1].data[..]).unwrap();
+        assert_eq!(
+            created,
+            ProposalCreated { id: proposal_id, proposer: accounts.bob, proposal_type: ProposalType::Technical }
+        );
+
+        let cast = VoteCast::decode(&mut &events[2].data[..]).unwrap();
+        assert_eq!(
+            cast,
+            VoteCast { proposal_id, voter: accounts.bob, option_index: 1, weight: 1 }
+        );
+    }
+
+    #[ink::test]
+    fn abstentions_count_toward_quorum() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        ink::env::test::set_caller(accounts.alice);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        assert!(contract.vote(proposal_id, 0).is_ok());
+
+        ink::env::test::set_caller(accounts.bob);
+        assert!(contract.abstain(proposal_id).is_ok());
+        assert_eq!(contract.abstain(proposal_id), Err(Error::AlreadyVoted));
+        assert_eq!(contract.vote(proposal_id, 0), Err(Error::AlreadyVoted));
+
+        let vote = contract.get_user_vote(proposal_id, accounts.bob).unwrap();
+        assert_eq!(vote.choice.option_index, ABSTAIN_INDEX);
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts, vec![1, 0]);
+        assert_eq!(proposal.abstain_count, 1);
+        assert_eq!(proposal.total_voters, 2);
+        assert_eq!(contract.get_total_votes(proposal_id), Ok(2));
+    }
+
+    #[ink::test]
+    fn abstain_majority_rejects_under_flag() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        // Only the owner may enable the rule
+        assert_eq!(contract.set_abstain_majority_rejects(true), Err(Error::NotAuthorized));
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_abstain_majority_rejects(true).unwrap();
+
+        let abstained = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        contract.vote(abstained, 0).unwrap();
+        for voter in [accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.abstain(abstained).unwrap();
+        }
+
+        advance_to_voting_end(&contract, abstained);
+        assert_eq!(contract.has_reached_quorum(abstained), Ok(true));
+        contract.update_proposal_status(abstained).unwrap();
+
+        let proposal = contract.get_proposal(abstained).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+        assert_eq!(proposal.rejection_reason, Some(RejectionReason::AbstainMajority));
+    }
+
+    #[ink::test]
+    fn abstain_majority_blocks_early_resolution() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 1, 1]);
+        contract.set_abstain_majority_rejects(true).unwrap();
+        contract.set_resolve_on_vote(true).unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        for voter in [accounts.alice, accounts.bob] {
+            ink::env::test::set_caller(voter);
+            contract.abstain(proposal_id).unwrap();
+        }
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 0).unwrap();
+
+        // Option 0 leads with every vote in, but abstentions hold the majority
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert_eq!(contract.get_effective_status(proposal_id), Ok(ProposalStatus::Active));
+
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+        assert_eq!(proposal.rejection_reason, Some(RejectionReason::AbstainMajority));
+    }
+
+    #[ink::test]
+    fn abstain_majority_allows_early_resolution_once_out_of_reach() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 10, 1]);
+        contract.set_abstain_majority_rejects(true).unwrap();
+        contract.set_resolve_on_vote(true).unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        contract.abstain(proposal_id).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+
+        // Charlie abstaining at full conviction still leaves abstentions behind
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+    }
+
+    #[ink::test]
+    fn abstain_majority_ignored_without_flag() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        ink::env::test::set_caller(accounts.alice);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        contract.vote(proposal_id, 0).unwrap();
+        for voter in [accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.abstain(proposal_id).unwrap();
+        }
+
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Passed);
+        assert_eq!(proposal.rejection_reason, None);
+    }
+
+    #[ink::test]
+    fn all_abstentions_reject_despite_quorum() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 1, 1]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        contract.abstain(proposal_id).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.abstain(proposal_id).unwrap();
+
+        advance_to_voting_end(&contract, proposal_id);
+        assert_eq!(contract.has_reached_quorum(proposal_id), Ok(true));
+        contract.update_proposal_status(proposal_id).unwrap();
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+        assert_eq!(proposal.rejection_reason, Some(RejectionReason::NoOptionVotes));
+        assert_eq!(contract.get_winning_option(proposal_id), Ok(None));
+    }
+
+    #[ink::test]
+    fn weighted_votes_produce_weighted_tally() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_voting_power(accounts.bob, 10).unwrap();
+        contract.set_voting_power(accounts.charlie, 3).unwrap();
+
+        for voter in [accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+        assert_eq!(contract.get_total_voting_power(), 13);
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 1).unwrap();
+
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![10, 3]);
+        assert_eq!(contract.get_user_vote(proposal_id, accounts.bob).unwrap().weight, 10);
+        assert_eq!(contract.get_winning_option(proposal_id), Ok(Some(("Yes".to_string(), 10))));
+    }
+
+    #[ink::test]
+    fn quorum_uses_registered_voting_power() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_voting_power(accounts.bob, 10).unwrap();
+        contract.set_voting_power(accounts.charlie, 3).unwrap();
+
+        for voter in [accounts.bob, accounts.charlie, accounts.django] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+        assert_eq!(contract.get_total_voting_power(), 14);
+
+        // 25% of 14 power requires 3, so Django's single vote (a third of
+        // the head count) is not enough
+        let governance_params = GovernanceParameters {
+            quorum_threshold: QuorumThreshold::TwentyFive,
+            ..default_governance_params()
+        };
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, governance_params);
+        contract.vote(proposal_id, 0).unwrap();
+
+        let results = contract.get_proposal_results(proposal_id).unwrap();
+        assert_eq!(results.quorum_required, 3);
+        assert!(!results.quorum_reached);
+
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 0).unwrap();
+        assert_eq!(contract.has_reached_quorum(proposal_id), Ok(true));
+
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+    }
+
+    #[ink::test]
+    fn total_voting_power_tracks_membership_changes() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+        assert_eq!(contract.get_total_voting_power(), 2);
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_voting_power(accounts.bob, 10).unwrap();
+        assert_eq!(contract.get_total_voting_power(), 11);
+
+        contract.set_blacklisted(accounts.bob, true).unwrap();
+        assert_eq!(contract.get_total_voting_power(), 1);
+        contract.set_blacklisted(accounts.bob, false).unwrap();
+        assert_eq!(contract.get_total_voting_power(), 11);
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.deregister_voter().unwrap();
+        assert_eq!(contract.get_total_voting_power(), 1);
+    }
+
+    #[ink::test]
+    fn quorum_denominator_breakdown_reflects_weights() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.bob, accounts.charlie, accounts.django] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_voting_power(accounts.bob, 10).unwrap();
+        contract.set_blacklisted(accounts.django, true).unwrap();
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        assert_eq!(contract.get_quorum_denominator_breakdown(proposal_id), Ok((3, 1, 11)));
+    }
+
+    #[ink::test]
+    fn proposal_cost_uses_type_then_global_deposit() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        assert_eq!(contract.get_proposal_cost(ProposalType::Treasury), 0);
+
+        contract.set_proposal_deposit(100).unwrap();
+        contract.set_type_deposit(ProposalType::Treasury, Some(500)).unwrap();
+        contract.set_type_deposit(ProposalType::Other, Some(10)).unwrap();
+
+        assert_eq!(contract.get_proposal_cost(ProposalType::Treasury), 500);
+        assert_eq!(contract.get_proposal_cost(ProposalType::Other), 10);
+        assert_eq!(contract.get_proposal_cost(ProposalType::Governance), 100);
+
+        // Clearing an override falls back to the global deposit
+        contract.set_type_deposit(ProposalType::Treasury, None).unwrap();
+        assert_eq!(contract.get_proposal_cost(ProposalType::Treasury), 100);
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.set_proposal_deposit(1), Err(Error::NotAuthorized));
+        assert_eq!(contract.set_type_deposit(ProposalType::Other, Some(1)), Err(Error::NotAuthorized));
+    }
+
+    #[ink::test]
+    fn change_vote_moves_weight_between_options() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_voting_power(accounts.bob, 4).unwrap();
+
+        for voter in [accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        let voting_options = VotingOptions {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+        };
+        let proposal_id = contract.create_proposal(
+            "Multi-option Proposal".to_string(),
+            "Test Description".to_string(),
+            ProposalType::Governance,
+            default_governance_params(),
+            voting_options,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ).unwrap();
+
+        // Never voted
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.change_vote(proposal_id, 1), Err(Error::NoExistingVote));
+
+        contract.vote(proposal_id, 0).unwrap();
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 0).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![5, 0, 0]);
+
+        // Move Bob from A to B, then to C
+        ink::env::test::set_caller(accounts.bob);
+        set_block(5);
+        assert!(contract.change_vote(proposal_id, 1).is_ok());
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![1, 4, 0]);
+        assert_eq!(contract.change_vote(proposal_id, 3), Err(Error::InvalidOptionIndex));
+
+        set_block(6);
+        assert!(contract.change_vote(proposal_id, 2).is_ok());
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts, vec![1, 0, 4]);
+        assert_eq!(proposal.total_voters, 2);
+
+        let vote = contract.get_user_vote(proposal_id, accounts.bob).unwrap();
+        assert_eq!(vote.choice.option_index, 2);
+        assert_eq!(vote.choice.option_text, "C");
+        assert_eq!(vote.timestamp, 6);
+        assert_eq!(vote.weight, 4);
+
+        // Closed once the voting period ends
+        advance_to_voting_end(&contract, proposal_id);
+        assert_eq!(contract.change_vote(proposal_id, 0), Err(Error::VotingPeriodEnded));
+
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.change_vote(proposal_id, 0), Err(Error::ProposalNotActive));
+    }
+
+    #[ink::test]
+    fn change_vote_from_abstention() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        contract.abstain(proposal_id).unwrap();
+        assert!(contract.change_vote(proposal_id, 1).is_ok());
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.abstain_count, 0);
+        assert_eq!(proposal.vote_counts, vec![0, 1]);
+    }
+
+    /// Create a three-option approval-mode proposal
+    fn create_approval_proposal(contract: &mut TreasuryGovernance, quorum_threshold: QuorumThreshold) -> u32 {
+        let governance_params = GovernanceParameters {
+            quorum_threshold,
+            voting_mode: VotingMode::Approval,
+            min_approval_bps: 0,
+            ..default_governance_params()
+        };
+        let voting_options = VotingOptions {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+        };
+        contract.create_proposal(
+            "Approval Proposal".to_string(),
+            "Test Description".to_string(),
+            ProposalType::Governance,
+            governance_params,
+            voting_options,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ).unwrap()
+    }
+
+    #[ink::test]
+    fn approval_ballot_counts_each_option() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        let proposal_id = create_approval_proposal(&mut contract, QuorumThreshold::Ten);
+        assert_eq!(contract.vote_approval(proposal_id, vec![0, 0]), Err(Error::InvalidOptionIndex));
+        assert_eq!(contract.vote_approval(proposal_id, vec![0, 3]), Err(Error::InvalidOptionIndex));
+        assert_eq!(contract.vote_approval(proposal_id, vec![]), Err(Error::InvalidOptionIndex));
+        assert!(contract.vote_approval(proposal_id, vec![0, 2]).is_ok());
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts, vec![1, 0, 1]);
+        assert_eq!(contract.get_user_vote(proposal_id, accounts.alice).unwrap().selections, vec![0, 2]);
+        assert_eq!(contract.get_total_votes(proposal_id), Ok(2));
+
+        // Approval ballots are rejected on single-choice proposals
+        let linear = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        assert_eq!(contract.vote_approval(linear, vec![0, 1]), Err(Error::InvalidVotingMode));
+    }
+
+    #[ink::test]
+    fn approval_quorum_counts_voters_once() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        // Eight voters, so a 25% quorum requires 2 weight
+        for i in 1..=8u8 {
+            ink::env::test::set_caller(ink::primitives::H160::from([i; 20]));
+            contract.register_voter().unwrap();
+        }
+
+        ink::env::test::set_caller(ink::primitives::H160::from([1; 20]));
+        let proposal_id = create_approval_proposal(&mut contract, QuorumThreshold::TwentyFive);
+
+        // One voter approving every option sums to 3 but is a single voter
+        assert!(contract.vote_approval(proposal_id, vec![0, 1, 2]).is_ok());
+        let results = contract.get_proposal_results(proposal_id).unwrap();
+        assert_eq!(results.quorum_required, 2);
+        assert_eq!(results.total_votes, 3);
+        assert!(!results.quorum_reached);
+
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+        assert_eq!(proposal.rejection_reason, Some(RejectionReason::QuorumNotReached));
+    }
+
+    #[ink::test]
+    fn deregister_voter_updates_registry() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+        assert_eq!(contract.get_total_voters(), 2);
+
+        ink::env::test::set_caller(accounts.bob);
+        assert!(contract.deregister_voter().is_ok());
+        assert_eq!(contract.get_total_voters(), 1);
+        assert!(!contract.is_registered_voter(accounts.bob));
+
+        // Cannot deregister twice, or without registering
+        assert_eq!(contract.deregister_voter(), Err(Error::NotAuthorized));
+        ink::env::test::set_caller(accounts.charlie);
+        assert_eq!(contract.deregister_voter(), Err(Error::NotAuthorized));
+
+        // Re-registration is possible afterwards
+        ink::env::test::set_caller(accounts.bob);
+        assert!(contract.register_voter().is_ok());
+        assert_eq!(contract.get_total_voters(), 2);
+    }
+
+    #[ink::test]
+    fn deregister_voter_keeps_cast_votes() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        contract.vote(proposal_id, 1).unwrap();
+        contract.deregister_voter().unwrap();
+
+        // The vote record and tally are untouched
+        let vote = contract.get_user_vote(proposal_id, accounts.bob).unwrap();
+        assert_eq!(vote.choice.option_index, 1);
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts, vec![0, 1]);
+        assert_eq!(proposal.total_voters, 1);
+
+        // But they can no longer vote on new proposals
+        let next = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        assert_eq!(contract.vote(next, 0), Err(Error::NotAuthorized));
+    }
+
+    /// Lock `amount` for weight as the current caller
+    fn lock_deposit(contract: &mut TreasuryGovernance, amount: u128) -> Result<()> {
+        ink::env::test::transfer_in(ink::U256::from(amount));
+        let result = contract.lock_for_weight();
+        ink::env::test::set_value_transferred(ink::U256::zero());
+        result
+    }
+
+    #[ink::test]
+    fn lock_for_weight_sets_voting_weight() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+        assert_eq!(lock_deposit(&mut contract, 50), Err(Error::DepositWeightingDisabled));
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_deposit_weighting(true).unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        lock_deposit(&mut contract, 50).unwrap();
+        lock_deposit(&mut contract, 25).unwrap();
+        assert_eq!(contract.get_locked_deposit(accounts.bob), 75);
+        assert_eq!(contract.get_voter_weight(accounts.bob), 75);
+        assert_eq!(contract.get_total_voting_power(), 76);
+
+        // Unregistered accounts cannot buy weight
+        ink::env::test::set_caller(accounts.charlie);
+        assert_eq!(lock_deposit(&mut contract, 10), Err(Error::VoterNotRegistered));
+    }
+
+    #[ink::test]
+    fn locked_weight_counts_in_votes() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_deposit_weighting(true).unwrap();
+        contract.register_voter().unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+        lock_deposit(&mut contract, 40).unwrap();
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        contract.vote(proposal_id, 1).unwrap();
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts, vec![0, 40]);
+    }
+
+    #[ink::test]
+    fn unlock_weight_blocked_by_active_vote() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_deposit_weighting(true).unwrap();
+        contract.register_voter().unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+        assert_eq!(contract.unlock_weight(), Err(Error::NoDeposit));
+        lock_deposit(&mut contract, 40).unwrap();
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        contract.vote(proposal_id, 0).unwrap();
+        assert_eq!(contract.unlock_weight(), Err(Error::ActiveVotesExist));
+
+        // Once the proposal is resolved the funds come back
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        let balance_before = ink::env::test::get_contract_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+        contract.unlock_weight().unwrap();
+        let balance_after = ink::env::test::get_contract_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+
+        assert_eq!(balance_after - balance_before, ink::U256::from(40));
+        assert_eq!(contract.get_locked_deposit(accounts.bob), 0);
+        assert_eq!(contract.get_voter_weight(accounts.bob), 0);
+        assert_eq!(contract.get_total_voting_power(), 1);
+    }
+
+    #[ink::test]
+    fn transfer_ownership_two_step() {
+        use scale::Decode;
+
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        assert_eq!(contract.get_owner(), accounts.alice);
+
+        contract.transfer_ownership(accounts.bob).unwrap();
+        assert_eq!(contract.get_pending_owner(), Some(accounts.bob));
+        // Nothing changes until the nominee accepts
+        assert_eq!(contract.get_owner(), accounts.alice);
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.accept_ownership().unwrap();
+        assert_eq!(contract.get_owner(), accounts.bob);
+        assert_eq!(contract.get_pending_owner(), None);
+
+        // The new owner can use owner-only messages, the old one cannot
+        contract.set_resolve_on_vote(true).unwrap();
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(contract.set_resolve_on_vote(false), Err(Error::NotAuthorized));
+
+        let events = ink::env::test::recorded_events();
+        let event = OwnershipTransferred::decode(&mut &events.last().unwrap().data[..]).unwrap();
+        assert_eq!(event, OwnershipTransferred {
+            previous_owner: accounts.alice,
+            new_owner: accounts.bob,
+        });
+    }
+
+    #[ink::test]
+    fn transfer_ownership_rejects_unauthorized() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.transfer_ownership(accounts.bob), Err(Error::NotAuthorized));
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.transfer_ownership(accounts.charlie).unwrap();
+
+        // Only the nominee can accept
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.accept_ownership(), Err(Error::NotAuthorized));
+        assert_eq!(contract.get_owner(), accounts.alice);
+    }
+
+    /// End-to-end run through weighted voting, snapshot quorum and the
+    /// execution delay, checking the proposal at every step
+    #[ink::test]
+    fn weighted_lifecycle_end_to_end() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        // Five voters holding 100 power in total
+        let weights = [
+            (accounts.alice, 10),
+            (accounts.bob, 30),
+            (accounts.charlie, 5),
+            (accounts.django, 20),
+            (accounts.eve, 35),
+        ];
+        for (voter, weight) in weights {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+            ink::env::test::set_caller(accounts.alice);
+            contract.set_voting_power(voter, weight).unwrap();
+        }
+        assert_eq!(contract.get_total_voters(), 5);
+        assert_eq!(contract.get_total_voting_power(), 100);
+
+        // 25% quorum of the 100 power snapshot: 25 required
+        let mut params = default_governance_params();
+        params.quorum_threshold = QuorumThreshold::TwentyFive;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, params);
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert_eq!(proposal.snapshot_voters, 5);
+        assert_eq!(proposal.snapshot_total_power, 100);
+        assert_eq!(contract.get_stats().active_proposals, 1);
+
+        // A late registration does not move the snapshot
+        ink::env::test::set_caller(accounts.frank);
+        contract.register_voter().unwrap();
+        assert_eq!(contract.get_total_voting_power(), 101);
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().snapshot_total_power, 100);
+
+        // Charlie and Django vote No: 25 power, exactly the quorum
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 1).unwrap();
+        assert!(!contract.has_reached_quorum(proposal_id).unwrap());
+        ink::env::test::set_caller(accounts.django);
+        contract.vote(proposal_id, 1).unwrap();
+        assert!(contract.has_reached_quorum(proposal_id).unwrap());
+
+        // Bob outweighs them both on Yes
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts, vec![30, 25]);
+        assert_eq!(proposal.total_voters, 3);
+        assert_eq!(proposal.participating_weight, 55);
+        assert_eq!(contract.get_user_vote(proposal_id, accounts.bob).unwrap().weight, 30);
+
+        // Nothing resolves while voting is open
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Active);
+
+        // After the period closes the weighted majority wins
+        advance_to_voting_end(&contract, proposal_id);
+        assert_eq!(contract.vote(proposal_id, 0), Err(Error::VotingPeriodEnded));
+        contract.update_proposal_status(proposal_id).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Passed);
+        assert_eq!(proposal.rejection_reason, None);
+        assert_eq!(
+            contract.get_winning_option(proposal_id).unwrap(),
+            Some(("Yes".to_string(), 30))
+        );
+        assert_eq!(contract.get_stats().active_proposals, 0);
+
+        // Execution waits for the delay
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(contract.execute_proposal(proposal_id), Err(Error::ExecutionDelayNotMet));
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+
+        advance_to_execution_time(&contract, proposal_id);
+        contract.execute_proposal(proposal_id).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Executed);
+        assert_eq!(contract.get_last_activity(), proposal.execution_time);
+        assert_eq!(contract.get_stats().executed_proposals, 1);
+
+        // And only once
+        assert_eq!(contract.execute_proposal(proposal_id), Err(Error::ProposalNotReadyForExecution));
+    }
+
+    #[ink::test]
+    fn proposer_can_cancel_proposal() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        ink::env::test::set_caller(accounts.bob);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        assert_eq!(contract.get_stats().active_proposals, 1);
+
+        contract.cancel_proposal(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Cancelled);
+        assert_eq!(contract.get_stats().active_proposals, 0);
+
+        // Cancelled proposals stay cancelled
+        assert_eq!(contract.cancel_proposal(proposal_id), Err(Error::ProposalNotActive));
+        contract.register_voter().unwrap();
+        assert_eq!(contract.vote(proposal_id, 0), Err(Error::ProposalNotActive));
+    }
+
+    #[ink::test]
+    fn owner_can_cancel_proposal() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        ink::env::test::set_caller(accounts.bob);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.cancel_proposal(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Cancelled);
+    }
+
+    #[ink::test]
+    fn third_party_cannot_cancel_proposal() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        ink::env::test::set_caller(accounts.bob);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        ink::env::test::set_caller(accounts.charlie);
+        assert_eq!(contract.cancel_proposal(proposal_id), Err(Error::NotAuthorized));
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Active);
+    }
+
+    #[ink::test]
+    fn quorum_shortfall_reports_gap() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        // 20 voters at a 25% quorum: 5 votes required
+        for i in 1..=20u8 {
+            ink::env::test::set_caller(ink::primitives::H160::from([i; 20]));
+            contract.register_voter().unwrap();
+        }
+        let mut params = default_governance_params();
+        params.quorum_threshold = QuorumThreshold::TwentyFive;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, params);
+        assert_eq!(contract.get_quorum_shortfall(proposal_id), Ok(5));
+
+        for i in 1..=3u8 {
+            ink::env::test::set_caller(ink::primitives::H160::from([i; 20]));
+            contract.vote(proposal_id, 0).unwrap();
+        }
+        assert_eq!(contract.get_quorum_shortfall(proposal_id), Ok(2));
+        assert_eq!(contract.get_quorum_shortfall(99), Err(Error::ProposalNotFound));
+    }
+
+    #[ink::test]
+    fn quorum_shortfall_zero_past_quorum() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        for voter in [accounts.alice, accounts.bob] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        contract.vote(proposal_id, 0).unwrap();
+        ink::env::test::set_caller(accounts.alice);
+        contract.vote(proposal_id, 0).unwrap();
+
+        assert!(contract.has_reached_quorum(proposal_id).unwrap());
+        assert_eq!(contract.get_quorum_shortfall(proposal_id), Ok(0));
+    }
+
+    /// Register alice, bob and charlie with the given weights, alice as owner
+    fn weighted_trio(weights: [u128; 3]) -> TreasuryGovernance {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        for (voter, weight) in [accounts.alice, accounts.bob, accounts.charlie].into_iter().zip(weights) {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+            ink::env::test::set_caller(accounts.alice);
+            contract.set_voting_power(voter, weight).unwrap();
+        }
+        contract
+    }
+
+    #[ink::test]
+    fn supermajority_passes_before_voting_end() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 96, 2]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+
+        // The other 4 cannot catch up even at the highest conviction
+        contract.update_proposal_status(proposal_id).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert!(contract.current_block() <= proposal.voting_end);
+        assert_eq!(proposal.status, ProposalStatus::Passed);
+    }
+
+    #[ink::test]
+    fn close_race_waits_for_voting_end() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([25, 40, 35]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 1).unwrap();
+
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Active);
+
+        // The normal end-of-period rules still apply
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+    }
+
+    #[ink::test]
+    fn critical_event_level_skips_vote_events() {
+        use scale::Decode;
+
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        assert_eq!(contract.get_event_level(), EventLevel::All);
+        contract.register_voter().unwrap();
+        contract.set_event_level(EventLevel::Critical).unwrap();
+
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, params);
+        let before_vote = ink::env::test::recorded_events().len();
+        contract.vote(proposal_id, 0).unwrap();
+        assert_eq!(ink::env::test::recorded_events().len(), before_vote);
+
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        contract.execute_proposal(proposal_id).unwrap();
+
+        let events = ink::env::test::recorded_events();
+        assert_eq!(events.len(), before_vote + 1);
+        let event = ProposalExecuted::decode(&mut &events.last().unwrap().data[..]).unwrap();
+        assert_eq!(event, ProposalExecuted {
+            id: proposal_id,
+            executor: accounts.alice,
+            block: contract.current_block(),
+        });
+    }
+
+    #[ink::test]
+    fn event_level_off_emits_nothing() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_event_level(EventLevel::Off).unwrap();
+
+        contract.register_voter().unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        contract.vote(proposal_id, 0).unwrap();
+        assert_eq!(ink::env::test::recorded_events().len(), 0);
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.set_event_level(EventLevel::All), Err(Error::NotAuthorized));
+    }
+
+    #[ink::test]
+    fn proposal_listing_is_paged() {
+        let mut contract = TreasuryGovernance::new();
+        for _ in 0..100 {
+            create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+        }
+
+        assert_eq!(contract.get_proposal_ids_paged(0, 10), (1..=10).collect::<Vec<u32>>());
+        assert_eq!(contract.get_proposal_ids_paged(95, 10), (96..=100).collect::<Vec<u32>>());
+        // Limit is capped at 50
+        assert_eq!(contract.get_proposal_ids_paged(20, 1000), (21..=70).collect::<Vec<u32>>());
+        assert!(contract.get_proposal_ids_paged(100, 10).is_empty());
+        assert!(contract.get_proposal_ids_paged(u32::MAX, 10).is_empty());
+
+        let page = contract.get_proposals_paged(40, 3);
+        assert_eq!(page.iter().map(|p| p.id).collect::<Vec<u32>>(), vec![41, 42, 43]);
+        assert_eq!(contract.get_proposals_paged(0, 1000).len(), 50);
+        assert!(contract.get_proposals_paged(200, 5).is_empty());
+    }
+
+    #[ink::test]
+    fn transfer_proposal_changes_authorship() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        ink::env::test::set_caller(accounts.bob);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        ink::env::test::set_caller(accounts.charlie);
+        assert_eq!(contract.transfer_proposal(proposal_id, accounts.charlie), Err(Error::NotAuthorized));
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.transfer_proposal(proposal_id, accounts.charlie).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().proposer, accounts.charlie);
+
+        // The old proposer has lost control, the new one has it
+        assert_eq!(contract.cancel_proposal(proposal_id), Err(Error::NotAuthorized));
+        assert_eq!(contract.transfer_proposal(proposal_id, accounts.bob), Err(Error::NotAuthorized));
+        ink::env::test::set_caller(accounts.charlie);
+        contract.cancel_proposal(proposal_id).unwrap();
+
+        // Resolved proposals can no longer change hands
+        assert_eq!(contract.transfer_proposal(proposal_id, accounts.bob), Err(Error::ProposalNotActive));
+    }
+
+    #[ink::test]
+    fn owner_can_transfer_proposal() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        ink::env::test::set_caller(accounts.bob);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.transfer_proposal(proposal_id, accounts.django).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().proposer, accounts.django);
+    }
+
+    #[ink::test]
+    fn proposals_by_status_and_type() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        let treasury_a = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        let governance = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let treasury_b = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        let technical = create_test_proposal(&mut contract, ProposalType::Technical, default_governance_params());
+        contract.cancel_proposal(treasury_b).unwrap();
+        contract.cancel_proposal(technical).unwrap();
+
+        assert_eq!(contract.get_proposals_by_type(ProposalType::Treasury), vec![treasury_a, treasury_b]);
+        assert_eq!(contract.get_proposals_by_type(ProposalType::Governance), vec![governance]);
+        assert!(contract.get_proposals_by_type(ProposalType::Other).is_empty());
+
+        assert_eq!(contract.get_proposals_by_status(ProposalStatus::Active), vec![treasury_a, governance]);
+        assert_eq!(contract.get_proposals_by_status(ProposalStatus::Cancelled), vec![treasury_b, technical]);
+        assert!(contract.get_proposals_by_status(ProposalStatus::Executed).is_empty());
+    }
+
+    #[ink::test]
+    fn min_quorum_one_floors_small_registries() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        // 10% of 3 voters floors to 0
+        ink::env::test::set_caller(accounts.alice);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        assert_eq!(contract.get_proposal_results(proposal_id).unwrap().quorum_required, 0);
+        assert!(contract.has_reached_quorum(proposal_id).unwrap());
+
+        contract.set_min_quorum_one(true).unwrap();
+        assert_eq!(contract.get_proposal_results(proposal_id).unwrap().quorum_required, 1);
+        assert!(!contract.has_reached_quorum(proposal_id).unwrap());
+        assert_eq!(contract.get_quorum_shortfall(proposal_id), Ok(1));
+
+        contract.vote(proposal_id, 0).unwrap();
+        assert!(contract.has_reached_quorum(proposal_id).unwrap());
+    }
+
+    #[ink::test]
+    fn min_quorum_one_ignores_empty_registry() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_min_quorum_one(true).unwrap();
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, default_governance_params());
+        assert_eq!(contract.get_proposal_results(proposal_id).unwrap().quorum_required, 0);
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.set_min_quorum_one(false), Err(Error::NotAuthorized));
+    }
+
+    /// Create a Yes/No proposal as the current caller, paying `deposit`
+    fn create_with_deposit(contract: &mut TreasuryGovernance, deposit: u128) -> Result<u32> {
+        ink::env::test::transfer_in(ink::U256::from(deposit));
+        let result = contract.create_proposal(
+            "Test Proposal".to_string(),
+            "Test Description".to_string(),
+            ProposalType::Treasury,
+            default_governance_params(),
+            yes_no_options(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        ink::env::test::set_value_transferred(ink::U256::zero());
+        result
+    }
+
+    #[ink::test]
+    fn proposal_deposit_below_cost_rejected() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_proposal_deposit(100).unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(create_with_deposit(&mut contract, 50), Err(Error::InsufficientDeposit));
+        assert!(contract.get_all_proposal_ids().is_empty());
+
+        let proposal_id = create_with_deposit(&mut contract, 100).unwrap();
+        assert_eq!(contract.get_proposal_deposit(proposal_id), Some((accounts.bob, 100)));
+    }
+
+    #[ink::test]
+    fn proposal_deposit_refunded_after_resolution() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        contract.set_proposal_deposit(100).unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        let proposal_id = create_with_deposit(&mut contract, 100).unwrap();
+        assert_eq!(contract.claim_deposit(proposal_id), Err(Error::DepositNotRefundable));
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.vote(proposal_id, 0).unwrap();
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+
+        ink::env::test::set_caller(accounts.charlie);
+        assert_eq!(contract.claim_deposit(proposal_id), Err(Error::NotAuthorized));
+
+        ink::env::test::set_caller(accounts.bob);
+        let balance_before = ink::env::test::get_contract_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+        contract.claim_deposit(proposal_id).unwrap();
+        let balance_after = ink::env::test::get_contract_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+        assert_eq!(balance_after - balance_before, ink::U256::from(100));
+
+        // Only once
+        assert_eq!(contract.claim_deposit(proposal_id), Err(Error::NoDeposit));
+    }
+
+    #[ink::test]
+    fn cancelled_proposal_forfeits_deposit() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_proposal_deposit(100).unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        let proposal_id = create_with_deposit(&mut contract, 100).unwrap();
+        contract.cancel_proposal(proposal_id).unwrap();
+        assert_eq!(contract.claim_deposit(proposal_id), Err(Error::DepositNotRefundable));
+    }
+
+    #[ink::test]
+    fn execute_treasury_proposal_transfers_funds() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        let treasury = ink::env::test::callee();
+        ink::env::test::set_contract_balance(treasury, ink::U256::from(1_000));
+
+        let proposal_id = passed_treasury_proposal(&mut contract, false);
+        advance_to_execution_time(&contract, proposal_id);
+
+        let balance = |account| ink::env::test::get_contract_balance::<ink::env::DefaultEnvironment>(account).unwrap();
+        let recipient_before = balance(accounts.django);
+        contract.execute_proposal(proposal_id).unwrap();
+
+        assert_eq!(balance(accounts.django) - recipient_before, ink::U256::from(100));
+        assert_eq!(balance(treasury), ink::U256::from(900));
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Executed);
+    }
+
+    #[ink::test]
+    fn execute_treasury_proposal_fails_without_funds() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        ink::env::test::set_contract_balance(ink::env::test::callee(), ink::U256::from(50));
+
+        let proposal_id = passed_treasury_proposal(&mut contract, false);
+        advance_to_execution_time(&contract, proposal_id);
+
+        assert_eq!(contract.execute_proposal(proposal_id), Err(Error::TransferFailed));
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+    }
+
+    #[ink::test]
+    fn executor_sees_ready_proposals() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        let ready = create_test_proposal(&mut contract, ProposalType::Governance, params.clone());
+        contract.vote(ready, 0).unwrap();
+        let queued = create_test_proposal(&mut contract, ProposalType::Governance, params.clone());
+        contract.vote(queued, 0).unwrap();
+        params.execution_delay = ExecutionDelay::SevenDays;
+        let waiting = create_test_proposal(&mut contract, ProposalType::Governance, params.clone());
+        contract.vote(waiting, 0).unwrap();
+        let _active = create_test_proposal(&mut contract, ProposalType::Governance, params);
+
+        advance_to_voting_end(&contract, ready);
+        contract.update_proposal_status(ready).unwrap();
+        contract.update_proposal_status(queued).unwrap();
+        contract.update_proposal_status(waiting).unwrap();
+
+        // Unrestricted: anyone sees the queue head, but not the ready
+        // proposal queued behind it
+        contract.set_restrict_execution(false).unwrap();
+        ink::env::test::set_caller(accounts.eve);
+        assert_eq!(contract.get_my_executable(0, 10), vec![ready]);
+        assert!(contract.get_my_executable(1, 10).is_empty());
+        assert!(contract.get_my_executable(0, 0).is_empty());
+        assert_eq!(contract.execute_proposal(queued), Err(Error::NotNextInQueue));
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_restrict_execution(true).unwrap();
+        contract.set_executor(accounts.bob, true).unwrap();
+        assert!(contract.is_executor(accounts.bob));
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.get_my_executable(0, 10), vec![ready]);
+        contract.execute_proposal(ready).unwrap();
+        assert_eq!(contract.get_my_executable(0, 10), vec![queued]);
+        contract.execute_proposal(queued).unwrap();
+
+        // The new head is still in its execution delay
+        assert_eq!(contract.get_execution_queue(), vec![waiting]);
+        assert!(contract.get_my_executable(0, 10).is_empty());
+    }
+
+    #[ink::test]
+    fn non_executor_sees_nothing_by_default() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        contract.vote(proposal_id, 0).unwrap();
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+
+        ink::env::test::set_caller(accounts.charlie);
+        assert!(contract.get_my_executable(0, 10).is_empty());
+        assert_eq!(contract.execute_proposal(proposal_id), Err(Error::NotAuthorized));
+
+        // Opening execution lets anyone run it
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_restrict_execution(false).unwrap();
+        ink::env::test::set_caller(accounts.charlie);
+        assert_eq!(contract.get_my_executable(0, 10), vec![proposal_id]);
+        contract.execute_proposal(proposal_id).unwrap();
+    }
+
+    #[ink::test]
+    fn clean_early_cancel_refunds_deposit() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_proposal_deposit(100).unwrap();
+        contract.set_refund_clean_cancellations(true).unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        let proposal_id = create_with_deposit(&mut contract, 100).unwrap();
+
+        let balance_before = ink::env::test::get_contract_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+        contract.cancel_proposal(proposal_id).unwrap();
+        let balance_after = ink::env::test::get_contract_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+
+        assert_eq!(balance_after - balance_before, ink::U256::from(100));
+        assert_eq!(contract.get_proposal_deposit(proposal_id), None);
+    }
+
+    #[ink::test]
+    fn cancel_after_votes_forfeits_deposit() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        contract.set_proposal_deposit(100).unwrap();
+        contract.set_refund_clean_cancellations(true).unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        let voted = create_with_deposit(&mut contract, 100).unwrap();
+        let slashed = create_with_deposit(&mut contract, 100).unwrap();
+        ink::env::test::set_caller(accounts.alice);
+        contract.vote(voted, 0).unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        let balance_before = ink::env::test::get_contract_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+        contract.cancel_proposal(voted).unwrap();
+
+        // An owner cancellation is a slash, even without votes
+        ink::env::test::set_caller(accounts.alice);
+        contract.cancel_proposal(slashed).unwrap();
+
+        let balance_after = ink::env::test::get_contract_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+        assert_eq!(balance_after, balance_before);
+        assert_eq!(contract.get_proposal_deposit(voted), Some((accounts.bob, 100)));
+        assert_eq!(contract.get_proposal_deposit(slashed), Some((accounts.bob, 100)));
+    }
+
+    #[ink::test]
+    fn proposal_threshold_gates_creation() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        for voter in [accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_voting_power(accounts.bob, 5).unwrap();
+        contract.set_proposal_threshold(5).unwrap();
+        assert_eq!(contract.get_proposal_threshold(), 5);
+
+        // Charlie holds the default weight of 1, the unregistered Django none
+        for account in [accounts.charlie, accounts.django] {
+            ink::env::test::set_caller(account);
+            assert_eq!(
+                contract.create_proposal(
+                    "Test Proposal".to_string(),
+                    "Test Description".to_string(),
+                    ProposalType::Other,
+                    default_governance_params(),
+                    yes_no_options(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                ),
+                Err(Error::BelowProposalThreshold)
+            );
+        }
+
+        ink::env::test::set_caller(accounts.bob);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().proposer, accounts.bob);
+    }
+
+    #[ink::test]
+    fn proposer_lifetime_cap_enforced() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_max_proposals_per_proposer(2).unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        let first = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+        create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+        assert_eq!(contract.get_proposals_created(accounts.bob), 2);
+
+        // Cancelling does not free up the lifetime allowance
+        contract.cancel_proposal(first).unwrap();
+        assert_eq!(
+            contract.create_proposal(
+                "Test Proposal".to_string(),
+                "Test Description".to_string(),
+                ProposalType::Other,
+                default_governance_params(),
+                yes_no_options(),
+                None,
+                None,
+                None,
+                None,
+                None,
+            ),
+            Err(Error::ProposalLimitReached)
+        );
+
+        // Other proposers are unaffected
+        ink::env::test::set_caller(accounts.charlie);
+        create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+        assert_eq!(contract.get_proposals_created(accounts.charlie), 1);
+    }
+
+    #[ink::test]
+    fn pause_blocks_mutating_messages() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        let voted = create_test_proposal(&mut contract, ProposalType::Other, params.clone());
+        contract.vote(voted, 0).unwrap();
+        let open = create_test_proposal(&mut contract, ProposalType::Other, params.clone());
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.set_paused(true), Err(Error::NotAuthorized));
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_paused(true).unwrap();
+        assert!(contract.is_paused());
+
+        assert_eq!(
+            contract.create_proposal(
+                "Test Proposal".to_string(),
+                "Test Description".to_string(),
+                ProposalType::Other,
+                params,
+                yes_no_options(),
+                None,
+                None,
+                None,
+                None,
+                None,
+            ),
+            Err(Error::ContractPaused)
+        );
+        assert_eq!(contract.vote(open, 0), Err(Error::ContractPaused));
+        assert_eq!(contract.abstain(open), Err(Error::ContractPaused));
+        assert_eq!(contract.change_vote(voted, 1), Err(Error::ContractPaused));
+
+        // Proposals still finalize, and queries still answer
+        advance_to_voting_end(&contract, voted);
+        contract.update_proposal_status(voted).unwrap();
+        assert_eq!(contract.get_proposal(voted).unwrap().status, ProposalStatus::Passed);
+        assert_eq!(contract.execute_proposal(voted), Err(Error::ContractPaused));
+
+        contract.set_paused(false).unwrap();
+        contract.execute_proposal(voted).unwrap();
+        assert_eq!(contract.get_proposal(voted).unwrap().status, ProposalStatus::Executed);
+    }
+
+    #[ink::test]
+    fn unpause_restores_voting() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+
+        contract.set_paused(true).unwrap();
+        assert_eq!(contract.vote(proposal_id, 0), Err(Error::ContractPaused));
+        contract.set_paused(false).unwrap();
+
+        contract.vote(proposal_id, 0).unwrap();
+        contract.change_vote(proposal_id, 1).unwrap();
+        create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+        assert_eq!(contract.get_all_proposal_ids().len(), 2);
+    }
+
+    #[ink::test]
+    fn lead_changes_recorded_as_votes_flip() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_track_lead_changes(true).unwrap();
+        for voter in [accounts.bob, accounts.charlie, accounts.django] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_voting_power(accounts.django, 3).unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+
+        // Bob puts Yes ahead
+        set_block(1);
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+
+        // Charlie ties it: no one leads alone, nothing recorded
+        set_block(2);
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 1).unwrap();
+
+        // Django puts No ahead
+        set_block(3);
+        ink::env::test::set_caller(accounts.django);
+        contract.vote(proposal_id, 1).unwrap();
+
+        // Django switching hands the lead back to Yes
+        set_block(4);
+        contract.change_vote(proposal_id, 0).unwrap();
+
+        assert_eq!(contract.get_lead_changes(proposal_id), Ok(vec![(1, 0), (3, 1), (4, 0)]));
+        assert_eq!(contract.get_lead_changes(99), Err(Error::ProposalNotFound));
+    }
+
+    #[ink::test]
+    fn lead_changes_not_recorded_by_default() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+        contract.vote(proposal_id, 0).unwrap();
+
+        assert_eq!(contract.get_lead_changes(proposal_id), Ok(Vec::new()));
+    }
+
+    /// Resolve a three-option proposal where option `i` receives `tally[i]`
+    /// votes from distinct voters
+    fn resolve_with_tally(tally: [u8; 3]) -> Proposal {
+        let mut contract = TreasuryGovernance::new();
+        let proposal_id = contract.create_proposal(
+            "Three Way".to_string(),
+            "Test Description".to_string(),
+            ProposalType::Other,
+            default_governance_params(),
+            VotingOptions {
+                options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            },
+            None,
+            None,
+            None,
+            None,
+            None,
+        ).unwrap();
+
+        let mut next_voter = 1u8;
+        for (option, &count) in tally.iter().enumerate() {
+            for _ in 0..count {
+                ink::env::test::set_caller(ink::primitives::H160::from([next_voter; 20]));
+                contract.register_voter().unwrap();
+                contract.vote(proposal_id, option as u32).unwrap();
+                next_voter += 1;
+            }
+        }
+
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        contract.get_proposal(proposal_id).unwrap()
+    }
+
+    #[ink::test]
+    fn late_winner_after_leading_tie_passes() {
+        let proposal = resolve_with_tally([3, 3, 5]);
+        assert_eq!(proposal.vote_counts, vec![3, 3, 5]);
+        assert_eq!(proposal.status, ProposalStatus::Passed);
+    }
+
+    #[ink::test]
+    fn shared_maximum_is_a_tie() {
+        let proposal = resolve_with_tally([5, 5, 2]);
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+        assert_eq!(proposal.rejection_reason, Some(RejectionReason::Tie));
+    }
+
+    #[ink::test]
+    fn single_clear_winner_passes() {
+        let proposal = resolve_with_tally([1, 4, 2]);
+        assert_eq!(proposal.status, ProposalStatus::Passed);
+        assert_eq!(proposal.rejection_reason, None);
+    }
+
+    /// Register alice, bob and charlie with weights 1, 2 and 4 and build the
+    /// chain alice -> bob -> charlie. Alice stays owner.
+    fn delegation_chain() -> TreasuryGovernance {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 2, 4]);
+        ink::env::test::set_caller(accounts.alice);
+        contract.delegate(accounts.bob).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.delegate(accounts.charlie).unwrap();
+        ink::env::test::set_caller(accounts.alice);
+        contract
+    }
+
+    #[ink::test]
+    fn delegation_depth_limits_flow() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = delegation_chain();
+
+        // Default depth 1: only Bob's weight reaches Charlie
+        assert_eq!(contract.get_max_delegation_depth(), 1);
+        assert_eq!(contract.get_effective_weight(accounts.charlie), 6);
+        assert_eq!(contract.get_effective_weight(accounts.bob), 0);
+        assert_eq!(contract.get_effective_weight(accounts.alice), 0);
+
+        contract.set_max_delegation_depth(2).unwrap();
+        assert_eq!(contract.get_effective_weight(accounts.charlie), 7);
+
+        // Deeper limits add nothing past the end of the chain
+        contract.set_max_delegation_depth(10).unwrap();
+        assert_eq!(contract.get_effective_weight(accounts.charlie), 7);
+
+        contract.set_max_delegation_depth(0).unwrap();
+        assert_eq!(contract.get_effective_weight(accounts.charlie), 4);
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.set_max_delegation_depth(3), Err(Error::NotAuthorized));
+    }
+
+    #[ink::test]
+    fn delegation_rejects_self_and_cycles() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = delegation_chain();
+
+        ink::env::test::set_caller(accounts.charlie);
+        assert_eq!(contract.delegate(accounts.charlie), Err(Error::InvalidDelegation));
+        assert_eq!(contract.delegate(accounts.alice), Err(Error::InvalidDelegation));
+        assert_eq!(contract.get_delegate(accounts.charlie), None);
+    }
+
+    #[ink::test]
+    fn result_queries_report_no_winner_on_tie() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 3, 3]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 1).unwrap();
+
+        let results = contract.get_proposal_results(proposal_id).unwrap();
+        assert_eq!(results.vote_counts, vec![3, 3]);
+        assert_eq!(results.winning_option, None);
+        assert_eq!(contract.get_winning_option(proposal_id), Ok(None));
+
+        // The status update agrees that nobody won
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().rejection_reason, Some(RejectionReason::Tie));
+    }
+
+    #[ink::test]
+    fn result_queries_agree_on_clear_winner() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 3, 3]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+
+        for voter in [accounts.alice, accounts.bob] {
+            ink::env::test::set_caller(voter);
+            contract.vote(proposal_id, 1).unwrap();
+        }
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 0).unwrap();
+
+        let results = contract.get_proposal_results(proposal_id).unwrap();
+        assert_eq!(results.winning_option, Some((1, "No".to_string(), 5)));
+        assert_eq!(contract.get_winning_option(proposal_id), Ok(Some(("No".to_string(), 5))));
+    }
+
+    #[ink::test]
+    fn pass_rate_by_type_counts_outcomes() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        let executed = create_test_proposal(&mut contract, ProposalType::Treasury, params.clone());
+        let passed = create_test_proposal(&mut contract, ProposalType::Treasury, params.clone());
+        let cancelled = create_test_proposal(&mut contract, ProposalType::Treasury, params.clone());
+        let _active = create_test_proposal(&mut contract, ProposalType::Treasury, params.clone());
+        let other = create_test_proposal(&mut contract, ProposalType::Governance, params);
+
+        for proposal_id in [executed, passed, other] {
+            contract.vote(proposal_id, 0).unwrap();
+        }
+        contract.cancel_proposal(cancelled).unwrap();
+        set_block(contract.get_proposal(executed).unwrap().voting_end + 1);
+        for proposal_id in [executed, passed, other] {
+            contract.update_proposal_status(proposal_id).unwrap();
+        }
+        contract.execute_proposal(executed).unwrap();
+
+        assert_eq!(contract.get_pass_rate_by_type(ProposalType::Treasury), (2, 4));
+        assert_eq!(contract.get_pass_rate_by_type(ProposalType::Governance), (1, 1));
+        assert_eq!(contract.get_pass_rate_by_type(ProposalType::Technical), (0, 0));
+    }
+
+    #[ink::test]
+    fn delegate_votes_with_combined_power() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 2, 4]);
+        for delegator in [accounts.alice, accounts.bob] {
+            ink::env::test::set_caller(delegator);
+            contract.delegate(accounts.charlie).unwrap();
+        }
+        assert_eq!(contract.get_delegators(accounts.charlie), vec![accounts.alice, accounts.bob]);
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+        assert_eq!(contract.vote(proposal_id, 1), Err(Error::VoteDelegated));
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 0).unwrap();
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts, vec![7, 0]);
+        assert_eq!(contract.get_user_vote(proposal_id, accounts.charlie).unwrap().weight, 7);
+        assert_eq!(contract.get_carried_by(proposal_id, accounts.alice), Some(accounts.charlie));
+
+        // Taking the weight back does not allow voting on top
+        ink::env::test::set_caller(accounts.alice);
+        contract.undelegate().unwrap();
+        assert_eq!(contract.vote(proposal_id, 1), Err(Error::AlreadyVoted));
+    }
+
+    #[ink::test]
+    fn delegator_who_voted_first_is_not_counted_twice() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 2, 4]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 1).unwrap();
+        contract.delegate(accounts.charlie).unwrap();
+
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 0).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![4, 2]);
+    }
+
+    #[ink::test]
+    fn undelegate_restores_own_vote() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 2, 4]);
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.undelegate(), Err(Error::NotDelegated));
+        contract.delegate(accounts.charlie).unwrap();
+        contract.undelegate().unwrap();
+        assert_eq!(contract.get_delegate(accounts.bob), None);
+        assert!(contract.get_delegators(accounts.charlie).is_empty());
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+        contract.vote(proposal_id, 1).unwrap();
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 0).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![4, 2]);
+    }
+
+    #[ink::test]
+    fn payout_within_reserve_succeeds() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        contract.set_treasury_reserve(900).unwrap();
+        let treasury = ink::env::test::callee();
+        ink::env::test::set_contract_balance(treasury, ink::U256::from(1_000));
+
+        // Paying 100 leaves exactly the reserve
+        let proposal_id = passed_treasury_proposal(&mut contract, false);
+        advance_to_execution_time(&contract, proposal_id);
+        contract.execute_proposal(proposal_id).unwrap();
+
+        assert_eq!(
+            ink::env::test::get_contract_balance::<ink::env::DefaultEnvironment>(treasury).unwrap(),
+            ink::U256::from(900)
+        );
+    }
+
+    #[ink::test]
+    fn payout_cannot_spend_locked_funds_or_deposits() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        contract.set_deposit_weighting(true).unwrap();
+        contract.set_type_deposit(ProposalType::Treasury, Some(30)).unwrap();
+        let treasury = ink::env::test::callee();
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+        lock_deposit(&mut contract, 100).unwrap();
+
+        ink::env::test::set_caller(accounts.alice);
+        ink::env::test::transfer_in(ink::U256::from(30));
+        let proposal_id = contract.create_proposal(
+            "Pay Django".to_string(),
+            "Test Description".to_string(),
+            ProposalType::Treasury,
+            default_governance_params(),
+            yes_no_options(),
+            Some(TreasuryAction { recipient: accounts.django, amount: 100 }),
+            None,
+            None,
+            None,
+            None,
+        ).unwrap();
+        ink::env::test::set_value_transferred(ink::U256::zero());
+        ink::env::test::set_contract_balance(treasury, ink::U256::from(130));
+        assert_eq!(contract.get_spendable_balance(), 0);
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        advance_to_execution_time(&contract, proposal_id);
+
+        // Bob's lock and Alice's deposit are not the treasury's to pay out
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(contract.execute_proposal(proposal_id), Err(Error::TransferFailed));
+        ink::env::test::set_contract_balance(treasury, ink::U256::from(230));
+        assert_eq!(contract.get_spendable_balance(), 100);
+        contract.execute_proposal(proposal_id).unwrap();
+
+        // Bob's lock is still fully available, and only the deposit remains
+        ink::env::test::set_caller(accounts.bob);
+        contract.unlock_weight().unwrap();
+        assert_eq!(
+            ink::env::test::get_contract_balance::<ink::env::DefaultEnvironment>(treasury).unwrap(),
+            ink::U256::from(30)
+        );
+        assert_eq!(contract.get_spendable_balance(), 0);
+    }
+
+    #[ink::test]
+    fn payout_breaching_reserve_rejected() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        contract.set_treasury_reserve(901).unwrap();
+        assert_eq!(contract.get_treasury_reserve(), 901);
+        ink::env::test::set_contract_balance(ink::env::test::callee(), ink::U256::from(1_000));
+
+        let proposal_id = passed_treasury_proposal(&mut contract, false);
+        advance_to_execution_time(&contract, proposal_id);
+
+        assert_eq!(contract.execute_proposal(proposal_id), Err(Error::ReserveViolation));
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+    }
+
+    #[ink::test]
+    fn single_heavy_voter_meets_power_quorum() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        // Ten voters, one holding 90 of the 99 power
+        for i in 1..=10u8 {
+            ink::env::test::set_caller(ink::primitives::H160::from([i; 20]));
+            contract.register_voter().unwrap();
+        }
+        let whale = ink::primitives::H160::from([1; 20]);
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_voting_power(whale, 90).unwrap();
+        assert_eq!(contract.get_total_voting_power(), 99);
+
+        let mut params = default_governance_params();
+        params.quorum_threshold = QuorumThreshold::TwentyFive;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, params);
+
+        // One voter in ten is below a 25% head count, but 90 of 99 power is not
+        ink::env::test::set_caller(whale);
+        contract.vote(proposal_id, 0).unwrap();
+        let results = contract.get_proposal_results(proposal_id).unwrap();
+        assert_eq!(results.quorum_required, 24);
+        assert_eq!(results.total_votes, 90);
+        assert!(results.quorum_reached);
+    }
+
+    #[ink::test]
+    fn count_registered_since_cutoff() {
+        let mut contract = TreasuryGovernance::new();
+
+        // Two voters at block 10, three at block 20, one at block 30
+        for (i, block) in [10, 10, 20, 20, 20, 30].into_iter().enumerate() {
+            set_block(block);
+            ink::env::test::set_caller(ink::primitives::H160::from([i as u8 + 1; 20]));
+            contract.register_voter().unwrap();
+        }
+        assert_eq!(contract.get_registered_at(ink::primitives::H160::from([3; 20])), Some(20));
+
+        assert_eq!(contract.count_registered_since(0), 6);
+        assert_eq!(contract.count_registered_since(10), 6);
+        assert_eq!(contract.count_registered_since(11), 4);
+        assert_eq!(contract.count_registered_since(20), 4);
+        assert_eq!(contract.count_registered_since(30), 1);
+        assert_eq!(contract.count_registered_since(31), 0);
+
+        // Leaving removes the voter from the count
+        ink::env::test::set_caller(ink::primitives::H160::from([4; 20]));
+        contract.deregister_voter().unwrap();
+        assert_eq!(contract.count_registered_since(20), 3);
+        assert_eq!(contract.get_registered_at(ink::primitives::H160::from([4; 20])), None);
+    }
+
+    #[ink::test]
+    fn count_registered_since_covers_batches_and_returning_voters() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        // A batch lands on a single block
+        set_block(10);
+        let batch: Vec<_> = (1..=50u8).map(|n| ink::H160::from([n; 20])).collect();
+        assert_eq!(contract.register_voters(batch), Ok(50));
+        set_block(20);
+        contract.register_voter().unwrap();
+        assert_eq!(contract.count_registered_since(10), 51);
+        assert_eq!(contract.count_registered_since(11), 1);
+
+        // A voter who leaves and returns counts at the new block only
+        ink::env::test::set_caller(ink::H160::from([7; 20]));
+        contract.deregister_voter().unwrap();
+        assert_eq!(contract.count_registered_since(0), 50);
+        set_block(30);
+        contract.register_voter().unwrap();
+        assert_eq!(contract.count_registered_since(0), 51);
+        assert_eq!(contract.count_registered_since(11), 2);
+        assert_eq!(contract.count_registered_since(21), 1);
+    }
+
+    #[ink::test]
+    fn custom_voting_period_in_blocks() {
+        let mut contract = TreasuryGovernance::new();
+        set_block(100);
+
+        // A twelve hour poll
+        let mut params = default_governance_params();
+        params.voting_period = VotingPeriod::Custom(12 * 60 * 10);
+        params.execution_delay = ExecutionDelay::Immediately;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, params);
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.voting_end, 100 + 7_200);
+        assert_eq!(proposal.execution_time, proposal.voting_end);
+        assert_eq!(VotingPeriod::Custom(MAX_CUSTOM_VOTING_PERIOD).to_blocks(), MAX_CUSTOM_VOTING_PERIOD);
+    }
+
+    #[ink::test]
+    fn custom_voting_period_out_of_bounds_rejected() {
+        let mut contract = TreasuryGovernance::new();
+
+        for blocks in [0, MIN_CUSTOM_VOTING_PERIOD - 1, MAX_CUSTOM_VOTING_PERIOD + 1] {
+            let mut params = default_governance_params();
+            params.voting_period = VotingPeriod::Custom(blocks);
+            assert_eq!(
+                contract.create_proposal(
+                    "Test Proposal".to_string(),
+                    "Test Description".to_string(),
+                    ProposalType::Other,
+                    params,
+                    yes_no_options(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                ),
+                Err(Error::InvalidVotingPeriod)
+            );
+        }
+
+        // The bounds themselves are allowed
+        for blocks in [MIN_CUSTOM_VOTING_PERIOD, MAX_CUSTOM_VOTING_PERIOD] {
+            let mut params = default_governance_params();
+            params.voting_period = VotingPeriod::Custom(blocks);
+            create_test_proposal(&mut contract, ProposalType::Other, params);
+        }
+        assert_eq!(contract.get_all_proposal_ids().len(), 2);
+    }
+
+    #[ink::test]
+    fn proposer_alone_cannot_meet_quorum_when_excluded() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([60, 20, 20]);
+        contract.set_exclude_proposer_from_quorum(true).unwrap();
+
+        // 25% of 100 requires 25; Alice's 60 alone no longer counts
+        let mut params = default_governance_params();
+        params.quorum_threshold = QuorumThreshold::TwentyFive;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, params);
+        contract.vote(proposal_id, 0).unwrap();
+        assert!(!contract.has_reached_quorum(proposal_id).unwrap());
+        assert_eq!(contract.get_quorum_shortfall(proposal_id), Ok(25));
+
+        // Independent participation still has to reach the bar
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+        assert!(!contract.has_reached_quorum(proposal_id).unwrap());
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 1).unwrap();
+        assert!(contract.has_reached_quorum(proposal_id).unwrap());
+    }
+
+    #[ink::test]
+    fn proposer_vote_counts_toward_quorum_by_default() {
+        let mut contract = weighted_trio([60, 20, 20]);
+        let mut params = default_governance_params();
+        params.quorum_threshold = QuorumThreshold::TwentyFive;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, params);
+        contract.vote(proposal_id, 0).unwrap();
+        assert!(contract.has_reached_quorum(proposal_id).unwrap());
+
+        contract.set_exclude_proposer_from_quorum(true).unwrap();
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+        assert_eq!(proposal.rejection_reason, Some(RejectionReason::QuorumNotReached));
+    }
+
+    #[ink::test]
+    fn zero_vote_proposal_expires() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Expired);
+        assert_eq!(proposal.rejection_reason, None);
+        assert_eq!(contract.get_stats().active_proposals, 0);
+    }
+
+    #[ink::test]
+    fn short_of_quorum_with_votes_is_rejected() {
+        let mut contract = TreasuryGovernance::new();
+        for i in 1..=20u8 {
+            ink::env::test::set_caller(ink::primitives::H160::from([i; 20]));
+            contract.register_voter().unwrap();
+        }
+
+        // One vote of 20 is below the 10% quorum
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+        contract.vote(proposal_id, 0).unwrap();
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+        assert_eq!(proposal.rejection_reason, Some(RejectionReason::QuorumNotReached));
+    }
+
+    #[ink::test]
+    fn batch_set_voter_weights() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+        }
+
+        ink::env::test::set_caller(accounts.alice);
+        let applied = contract.set_voter_weights_batch(vec![
+            (accounts.bob, 10),
+            (accounts.charlie, 25),
+            // Not registered: skipped
+            (accounts.django, 50),
+        ]).unwrap();
+
+        assert_eq!(applied, 2);
+        assert_eq!(contract.get_voter_weight(accounts.alice), 1);
+        assert_eq!(contract.get_voter_weight(accounts.bob), 10);
+        assert_eq!(contract.get_voter_weight(accounts.charlie), 25);
+        assert_eq!(contract.get_voter_weight(accounts.django), 0);
+        assert_eq!(contract.get_total_voting_power(), 36);
+    }
+
+    #[ink::test]
+    fn batch_set_voter_weights_limits() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        let too_many = (0..101u8).map(|i| (ink::primitives::H160::from([i; 20]), 2)).collect();
+        assert_eq!(contract.set_voter_weights_batch(too_many), Err(Error::BatchTooLarge));
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(
+            contract.set_voter_weights_batch(vec![(accounts.bob, 5)]),
+            Err(Error::NotAuthorized)
+        );
+    }
+
+    #[ink::test]
+    fn batch_register_fresh_voters() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        let added = contract.register_voters(vec![accounts.bob, accounts.charlie, accounts.django]).unwrap();
+        assert_eq!(added, 3);
+        assert_eq!(contract.get_total_voters(), 3);
+        assert_eq!(contract.get_total_voting_power(), 3);
+        assert!(contract.is_registered_voter(accounts.charlie));
+        assert!(!contract.is_registered_voter(accounts.alice));
+    }
+
+    #[ink::test]
+    fn batch_register_skips_duplicates() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+
+        ink::env::test::set_caller(accounts.alice);
+        let added = contract.register_voters(vec![accounts.bob, accounts.charlie, accounts.charlie]).unwrap();
+        assert_eq!(added, 1);
+        assert_eq!(contract.get_total_voters(), 2);
+    }
+
+    #[ink::test]
+    fn batch_register_rejects_oversized_batch() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        let too_many = (0..101u8).map(|i| ink::primitives::H160::from([i; 20])).collect();
+        assert_eq!(contract.register_voters(too_many), Err(Error::BatchTooLarge));
+        assert_eq!(contract.get_total_voters(), 0);
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.register_voters(vec![accounts.bob]), Err(Error::NotAuthorized));
+    }
+
+    #[ink::test]
+    fn reregistration_waits_for_cooldown() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_reregister_cooldown(100).unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+        set_block(50);
+        contract.deregister_voter().unwrap();
+
+        assert_eq!(contract.register_voter(), Err(Error::CooldownActive));
+        set_block(149);
+        assert_eq!(contract.register_voter(), Err(Error::CooldownActive));
+        assert!(!contract.is_registered_voter(accounts.bob));
+
+        set_block(150);
+        contract.register_voter().unwrap();
+        assert!(contract.is_registered_voter(accounts.bob));
+    }
+
+    #[ink::test]
+    fn reregistration_immediate_without_cooldown() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.bob);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        contract.deregister_voter().unwrap();
+        contract.register_voter().unwrap();
+        assert_eq!(contract.get_total_voters(), 1);
+    }
+
+    #[ink::test]
+    fn owner_registers_voter_on_behalf() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        contract.register_voter_for(accounts.bob).unwrap();
+        assert!(contract.is_registered_voter(accounts.bob));
+        assert_eq!(contract.get_total_voters(), 1);
+        assert_eq!(contract.register_voter_for(accounts.bob), Err(Error::AlreadyRegistered));
+
+        // Bob can vote as if he had registered himself
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+    }
+
+    #[ink::test]
+    fn non_owner_cannot_register_on_behalf() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.register_voter_for(accounts.charlie), Err(Error::NotAuthorized));
+        assert!(!contract.is_registered_voter(accounts.charlie));
+    }
+
+    #[ink::test]
+    fn effective_weight_combines_type_weight_and_delegation() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 2, 4]);
+        contract.set_type_weight(accounts.charlie, ProposalType::Technical, 10).unwrap();
+        contract.set_max_delegation_depth(2).unwrap();
+        for (delegator, delegate) in [(accounts.alice, accounts.bob), (accounts.bob, accounts.charlie)] {
+            ink::env::test::set_caller(delegator);
+            contract.delegate(delegate).unwrap();
+        }
+
+        let technical = create_test_proposal(&mut contract, ProposalType::Technical, default_governance_params());
+        let other = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+
+        // Charlie's type weight replaces his general weight, then both
+        // delegators in the chain add their general weight
+        assert_eq!(contract.compute_effective_weight(accounts.charlie, technical), Ok(13));
+        assert_eq!(contract.compute_effective_weight(accounts.charlie, other), Ok(7));
+        assert_eq!(contract.compute_effective_weight(accounts.bob, technical), Ok(0));
+        assert_eq!(contract.compute_effective_weight(accounts.django, technical), Ok(0));
+        assert_eq!(contract.compute_effective_weight(accounts.charlie, 99), Err(Error::ProposalNotFound));
+
+        // The computed weight is what the vote carries, and once used it is gone
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(technical, 0).unwrap();
+        assert_eq!(contract.get_user_vote(technical, accounts.charlie).unwrap().weight, 13);
+        assert_eq!(contract.compute_effective_weight(accounts.charlie, technical), Ok(0));
+    }
+
+    #[ink::test]
+    fn late_registrations_do_not_move_quorum() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([20, 40, 40]);
+
+        // 25% of the 100 power snapshot
+        let mut params = default_governance_params();
+        params.quorum_threshold = QuorumThreshold::TwentyFive;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, params.clone());
+        assert_eq!(contract.get_proposal_results(proposal_id).unwrap().quorum_required, 25);
+
+        // A wave of new, heavily weighted members joins afterwards
+        for i in 1..=10u8 {
+            let voter = ink::primitives::H160::from([i; 20]);
+            ink::env::test::set_caller(voter);
+            contract.register_voter().unwrap();
+            ink::env::test::set_caller(accounts.alice);
+            contract.set_voting_power(voter, 100).unwrap();
+        }
+        assert_eq!(contract.get_total_voting_power(), 1_100);
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.snapshot_total_power, 100);
+        assert_eq!(contract.get_proposal_results(proposal_id).unwrap().quorum_required, 25);
+
+        // New proposals see the larger registry
+        let next = create_test_proposal(&mut contract, ProposalType::Treasury, params);
+        assert_eq!(contract.get_proposal_results(next).unwrap().quorum_required, 275);
+    }
+
+    #[ink::test]
+    fn execution_hook_is_owner_configured() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        assert_eq!(contract.get_execution_hook(), None);
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(
+            contract.set_execution_hook(Some(accounts.django), true),
+            Err(Error::NotAuthorized)
+        );
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_execution_hook(Some(accounts.django), true).unwrap();
+        assert_eq!(contract.get_execution_hook(), Some(accounts.django));
+
+        contract.set_execution_hook(None, false).unwrap();
+        assert_eq!(contract.get_execution_hook(), None);
+    }
+
+    #[ink::test]
+    fn roles_are_granted_by_owner_and_admins() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+
+        // The owner holds every role implicitly
+        assert!(contract.has_role(accounts.alice, Role::Admin));
+        assert!(!contract.has_role(accounts.bob, Role::Admin));
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.grant_role(accounts.bob, Role::Pauser), Err(Error::NotAuthorized));
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.grant_role(accounts.bob, Role::Admin).unwrap();
+        assert!(contract.has_role(accounts.bob, Role::Admin));
+
+        // Admins manage every role but Admin itself
+        ink::env::test::set_caller(accounts.bob);
+        contract.grant_role(accounts.charlie, Role::Pauser).unwrap();
+        assert!(contract.has_role(accounts.charlie, Role::Pauser));
+        assert_eq!(contract.grant_role(accounts.charlie, Role::Admin), Err(Error::NotAuthorized));
+        contract.revoke_role(accounts.charlie, Role::Pauser).unwrap();
+        assert!(!contract.has_role(accounts.charlie, Role::Pauser));
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.revoke_role(accounts.bob, Role::Admin).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.grant_role(accounts.charlie, Role::Pauser), Err(Error::NotAuthorized));
+    }
+
+    #[ink::test]
+    fn pauser_role_controls_pause() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.django);
+        let mut contract = TreasuryGovernance::new();
+        contract.grant_role(accounts.bob, Role::Pauser).unwrap();
+
+        ink::env::test::set_caller(accounts.charlie);
+        assert_eq!(contract.set_paused(true), Err(Error::NotAuthorized));
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.set_paused(true).unwrap();
+        assert!(contract.is_paused());
+        contract.set_paused(false).unwrap();
+        assert!(!contract.is_paused());
+    }
+
+    #[ink::test]
+    fn executor_role_gates_restricted_execution() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        contract.vote(proposal_id, 0).unwrap();
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.execute_proposal(proposal_id), Err(Error::NotAuthorized));
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.grant_role(accounts.bob, Role::Executor).unwrap();
+        assert!(contract.is_executor(accounts.bob));
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.execute_proposal(proposal_id).unwrap();
+    }
+
+    #[ink::test]
+    fn proposer_role_bypasses_threshold() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_proposal_threshold(5).unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        let attempt = contract.create_proposal(
+            "Title".to_string(),
+            "Description".to_string(),
+            ProposalType::Governance,
+            default_governance_params(),
+            yes_no_options(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(attempt, Err(Error::BelowProposalThreshold));
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.grant_role(accounts.bob, Role::Proposer).unwrap();
+
+        ink::env::test::set_caller(accounts.bob);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().proposer, accounts.bob);
+    }
+
+    #[ink::test]
+    fn deployment_block_is_recorded() {
+        set_block(42);
+        let contract = TreasuryGovernance::new();
+        assert_eq!(contract.get_deployed_at(), 42);
+        assert_eq!(contract.blocks_since_deploy(), 0);
+
+        set_block(142);
+        assert_eq!(contract.get_deployed_at(), 42);
+        assert_eq!(contract.blocks_since_deploy(), 100);
+    }
+
+    #[ink::test]
+    fn vote_receipt_reports_running_tally() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([3, 5, 7]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+
+        let first = contract.vote_with_receipt(proposal_id, 0).unwrap();
+        assert_eq!(first.proposal_id, proposal_id);
+        assert_eq!(first.option_index, 0);
+        assert_eq!(first.weight, 3);
+        assert_eq!(first.new_total_for_option, 3);
+
+        ink::env::test::set_caller(accounts.bob);
+        let second = contract.vote_with_receipt(proposal_id, 0).unwrap();
+        assert_eq!(second.weight, 5);
+        assert_eq!(second.new_total_for_option, 8);
+        assert_eq!(second.timestamp, contract.get_user_vote(proposal_id, accounts.bob).unwrap().timestamp);
+
+        let results = contract.get_detailed_results(proposal_id).unwrap();
+        assert_eq!(results[0].1, second.new_total_for_option);
+    }
+
+    #[ink::test]
+    fn new_voters_vote_with_reduced_weight() {
+        let accounts = ink::env::test::default_accounts();
+        set_block(0);
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.register_voter().unwrap();
+        contract.set_voting_power(accounts.alice, 100).unwrap();
+        contract.set_maturity_weighting(true, 1000).unwrap();
+
+        set_block(1000);
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_voting_power(accounts.bob, 100).unwrap();
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        assert_eq!(contract.compute_effective_weight(accounts.alice, proposal_id), Ok(100));
+        assert_eq!(contract.compute_effective_weight(accounts.bob, proposal_id), Ok(50));
+
+        // Halfway through maturity bob carries three quarters
+        set_block(1500);
+        assert_eq!(contract.compute_effective_weight(accounts.bob, proposal_id), Ok(75));
+
+        set_block(1000);
+        contract.vote(proposal_id, 0).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 1).unwrap();
+        let results = contract.get_detailed_results(proposal_id).unwrap();
+        assert_eq!(results[0].1, 100);
+        assert_eq!(results[1].1, 50);
+    }
+
+    #[ink::test]
+    fn proposal_votes_are_listed_in_order() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 2, 3]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        assert_eq!(contract.get_proposal_votes(proposal_id, 0, 10), Ok(Vec::new()));
+
+        contract.vote(proposal_id, 0).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 1).unwrap();
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 0).unwrap();
+
+        let votes = contract.get_proposal_votes(proposal_id, 0, 10).unwrap();
+        let voters: Vec<_> = votes.iter().map(|vote| vote.voter).collect();
+        assert_eq!(voters, vec![accounts.alice, accounts.bob, accounts.charlie]);
+        assert_eq!(votes[1].choice.option_index, 1);
+        assert_eq!(votes[2].weight, 3);
+
+        let page = contract.get_proposal_votes(proposal_id, 1, 1).unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].voter, accounts.bob);
+
+        assert_eq!(contract.get_proposal_votes(99, 0, 10), Err(Error::ProposalNotFound));
+    }
+
+    #[ink::test]
+    fn dry_run_vote_mirrors_vote() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 3, 4]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+
+        assert_eq!(contract.dry_run_vote(proposal_id, 0, accounts.alice), Ok(2));
+        assert_eq!(contract.dry_run_vote(proposal_id, 0, accounts.eve), Err(Error::NotAuthorized));
+        assert_eq!(contract.dry_run_vote(99, 0, accounts.alice), Err(Error::ProposalNotFound));
+        assert_eq!(contract.dry_run_vote(proposal_id, 5, accounts.alice), Err(Error::InvalidOptionIndex));
+        assert_eq!(contract.vote(proposal_id, 5), Err(Error::InvalidOptionIndex));
+
+        // Dry runs leave no trace
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().total_voters, 0);
+
+        contract.set_blacklisted(accounts.charlie, true).unwrap();
+        assert_eq!(contract.dry_run_vote(proposal_id, 0, accounts.charlie), Err(Error::VoterBlacklisted));
+
+        contract.vote(proposal_id, 0).unwrap();
+        assert_eq!(contract.dry_run_vote(proposal_id, 1, accounts.alice), Err(Error::AlreadyVoted));
+
+        // The dry-run weight is what the vote records
+        assert_eq!(contract.dry_run_vote(proposal_id, 1, accounts.bob), Ok(3));
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.vote_with_receipt(proposal_id, 1).unwrap().weight, 3);
+
+        advance_to_voting_end(&contract, proposal_id);
+        assert_eq!(contract.dry_run_vote(proposal_id, 0, accounts.django), Err(Error::NotAuthorized));
+        ink::env::test::set_caller(accounts.alice);
+        contract.register_voter_for(accounts.django).unwrap();
+        assert_eq!(contract.dry_run_vote(proposal_id, 0, accounts.django), Err(Error::VotingPeriodEnded));
+
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.dry_run_vote(proposal_id, 0, accounts.django), Err(Error::ProposalNotActive));
+    }
+
+    #[ink::test]
+    fn voter_history_lists_each_proposal_once() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 1, 1]);
+        let first = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let skipped = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let second = create_test_proposal(&mut contract, ProposalType::Technical, default_governance_params());
+        let third = create_test_proposal(&mut contract, ProposalType::Other, default_governance_params());
+
+        contract.vote(first, 0).unwrap();
+        contract.vote(second, 1).unwrap();
+        contract.vote(third, 0).unwrap();
+        contract.change_vote(first, 1).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(skipped, 0).unwrap();
+
+        assert_eq!(contract.get_voter_history(accounts.alice), vec![first, second, third]);
+        let votes = contract.get_voter_votes(accounts.alice);
+        assert_eq!(votes.len(), 3);
+        assert_eq!(votes[0].choice.option_index, 1);
+        assert_eq!(contract.get_voter_history(accounts.charlie), Vec::<u32>::new());
+    }
+
+    #[ink::test]
+    fn finalization_caches_results() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 3, 4]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        contract.vote(proposal_id, 1).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+
+        // Active proposals are computed live
+        assert_eq!(contract.get_cached_results(proposal_id), None);
+        let live = contract.get_proposal_results(proposal_id).unwrap();
+
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+
+        let cached = contract.get_cached_results(proposal_id).unwrap();
+        assert_eq!(cached, live);
+        assert_eq!(contract.get_proposal_results(proposal_id), Ok(cached.clone()));
+        assert_eq!(cached.winning_option, Some((0, "Yes".to_string(), 3)));
+        assert_eq!(contract.get_winning_option(proposal_id), Ok(Some(("Yes".to_string(), 3))));
+        let detailed = contract.get_detailed_results(proposal_id).unwrap();
+        assert_eq!(detailed[0].1, cached.vote_counts[0]);
+        assert_eq!(detailed[1].1, cached.vote_counts[1]);
+
+        // Early resolution on a vote caches results as well
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_resolve_on_vote(true).unwrap();
+        let early = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote_with_conviction(early, 0, 5 * CONVICTION_PERIOD).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(early, 0).unwrap();
+        assert_eq!(contract.get_proposal(early).unwrap().status, ProposalStatus::Passed);
+        let cached = contract.get_cached_results(early).unwrap();
+        assert_eq!(cached.vote_counts, vec![27, 0]);
+        assert_eq!(contract.get_execution_queue(), vec![proposal_id, early]);
+    }
+
+    #[ink::test]
+    fn voting_option_cap_is_configurable() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        assert_eq!(contract.get_max_voting_options(), 10);
+        assert_eq!(contract.set_max_voting_options(0), Err(Error::InvalidVotingOptions));
+
+        let three_options = || VotingOptions {
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+        };
+        let propose = |contract: &mut TreasuryGovernance, options: VotingOptions| {
+            contract.create_proposal(
+                "Title".to_string(),
+                "Description".to_string(),
+                ProposalType::Governance,
+                default_governance_params(),
+                options,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+
+        contract.set_max_voting_options(2).unwrap();
+        assert_eq!(propose(&mut contract, three_options()), Err(Error::InvalidVotingOptions));
+        assert!(propose(&mut contract, yes_no_options()).is_ok());
+
+        contract.set_max_voting_options(20).unwrap();
+        assert!(propose(&mut contract, three_options()).is_ok());
+    }
+
+    #[ink::test]
+    fn option_labels_must_be_distinct_and_non_empty() {
+        let mut contract = TreasuryGovernance::new();
+        let propose = |contract: &mut TreasuryGovernance, labels: &[&str]| {
+            contract.create_proposal(
+                "Title".to_string(),
+                "Description".to_string(),
+                ProposalType::Governance,
+                default_governance_params(),
+                VotingOptions { options: labels.iter().map(|label| label.to_string()).collect() },
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+
+        assert_eq!(propose(&mut contract, &["Yes", "Yes"]), Err(Error::DuplicateVotingOption));
+        assert_eq!(propose(&mut contract, &["", "No"]), Err(Error::InvalidVotingOptions));
+        let too_long = "x".repeat(MAX_OPTION_LENGTH + 1);
+        assert_eq!(propose(&mut contract, &["Yes", &too_long]), Err(Error::InvalidVotingOptions));
+
+        // Labels are compared case-sensitively
+        assert!(propose(&mut contract, &["Yes", "yes", "No"]).is_ok());
+    }
+
+    #[ink::test]
+    fn unreachable_quorum_rejects_early() {
+        let mut contract = weighted_trio([1, 1, 1]);
+        contract.set_exclude_proposer_from_quorum(true).unwrap();
+        let mut params = default_governance_params();
+        params.min_absolute_weight = 3;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, params);
+
+        // All three could still vote, so the proposal stays open
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Active);
+
+        // The proposer's vote does not count, leaving at most 2 of the 3 needed
+        contract.vote(proposal_id, 0).unwrap();
+        contract.update_proposal_status(proposal_id).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert!(contract.current_block() <= proposal.voting_end);
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+        assert_eq!(proposal.rejection_reason, Some(RejectionReason::QuorumUnreachable));
+    }
+
+    #[ink::test]
+    fn proposals_can_use_default_parameters() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        assert_eq!(contract.get_default_governance_params(), default_governance_params());
+
+        let mut defaults = default_governance_params();
+        defaults.voting_period = VotingPeriod::ThirtyDays;
+        defaults.quorum_threshold = QuorumThreshold::TwentyFive;
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.set_default_governance_params(defaults.clone()), Err(Error::NotAuthorized));
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_default_governance_params(defaults.clone()).unwrap();
+
+        let proposal_id = contract.create_proposal_with_defaults(
+            "Title".to_string(),
+            "Description".to_string(),
+            ProposalType::Technical,
+            yes_no_options(),
+            None,
+        ).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().governance_params, defaults);
+    }
+
+    #[ink::test]
+    fn type_minimum_quorum_is_enforced() {
+        let mut contract = TreasuryGovernance::new();
+        contract.set_type_min_quorum(ProposalType::Technical, Some(QuorumThreshold::Twenty)).unwrap();
+
+        let propose = |contract: &mut TreasuryGovernance, proposal_type: ProposalType, quorum: QuorumThreshold| {
+            let mut params = default_governance_params();
+            params.quorum_threshold = quorum;
+            contract.create_proposal(
+                "Title".to_string(),
+                "Description".to_string(),
+                proposal_type,
+                params,
+                yes_no_options(),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+
+        assert_eq!(
+            propose(&mut contract, ProposalType::Technical, QuorumThreshold::Ten),
+            Err(Error::QuorumBelowMinimum)
+        );
+        assert!(propose(&mut contract, ProposalType::Technical, QuorumThreshold::TwentyFive).is_ok());
+        assert!(propose(&mut contract, ProposalType::Governance, QuorumThreshold::Five).is_ok());
+
+        contract.set_type_min_quorum(ProposalType::Technical, None).unwrap();
+        assert!(propose(&mut contract, ProposalType::Technical, QuorumThreshold::Five).is_ok());
+    }
+
+    #[ink::test]
+    fn finalize_and_execute_in_one_call() {
+        let mut contract = weighted_trio([2, 1, 1]);
+
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        contract.vote(proposal_id, 0).unwrap();
+
+        // Still voting: nothing changes
+        assert_eq!(contract.finalize_and_execute(proposal_id), Ok(ProposalStatus::Active));
+
+        advance_to_voting_end(&contract, proposal_id);
+        assert_eq!(contract.finalize_and_execute(proposal_id), Ok(ProposalStatus::Executed));
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Executed);
+    }
+
+    #[ink::test]
+    fn finalize_and_execute_waits_for_delay() {
+        let mut contract = weighted_trio([2, 1, 1]);
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        contract.vote(proposal_id, 0).unwrap();
+
+        advance_to_voting_end(&contract, proposal_id);
+        assert_eq!(contract.finalize_and_execute(proposal_id), Ok(ProposalStatus::Passed));
+
+        advance_to_execution_time(&contract, proposal_id);
+        assert_eq!(contract.finalize_and_execute(proposal_id), Ok(ProposalStatus::Executed));
+    }
+
+    #[ink::test]
+    fn finalize_and_execute_keeps_non_head_passed() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 1, 1]);
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        let head = create_test_proposal(&mut contract, ProposalType::Governance, params.clone());
+        let behind = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        contract.vote(head, 0).unwrap();
+        contract.vote(behind, 0).unwrap();
+
+        advance_to_voting_end(&contract, head);
+        contract.update_proposal_status(head).unwrap();
+
+        // Finalization sticks even though the proposal cannot run yet
+        assert_eq!(contract.finalize_and_execute(behind), Ok(ProposalStatus::Passed));
+        assert_eq!(contract.get_proposal(behind).unwrap().status, ProposalStatus::Passed);
+        assert_eq!(contract.get_execution_queue(), vec![head, behind]);
+
+        // Nor does a caller who may not execute revert it
+        ink::env::test::set_caller(accounts.charlie);
+        assert_eq!(contract.finalize_and_execute(head), Ok(ProposalStatus::Passed));
+
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(contract.finalize_and_execute(head), Ok(ProposalStatus::Executed));
+        assert_eq!(contract.finalize_and_execute(behind), Ok(ProposalStatus::Executed));
+    }
+
+    #[ink::test]
+    fn finalize_and_execute_keeps_dependent_passed_until_dependency_executes() {
+        let mut contract = weighted_trio([2, 1, 1]);
+        let budget = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let funding = create_dependent_proposal(&mut contract, budget).unwrap();
+        contract.vote(budget, 0).unwrap();
+        contract.vote(funding, 0).unwrap();
+
+        // The dependency passed but is still in its execution delay
+        advance_to_voting_end(&contract, budget);
+        contract.update_proposal_status(budget).unwrap();
+        assert_eq!(contract.finalize_and_execute(funding), Ok(ProposalStatus::Passed));
+        assert_eq!(contract.get_proposal(funding).unwrap().status, ProposalStatus::Passed);
+
+        advance_to_execution_time(&contract, budget);
+        assert_eq!(contract.finalize_and_execute(budget), Ok(ProposalStatus::Executed));
+        assert_eq!(contract.finalize_and_execute(funding), Ok(ProposalStatus::Executed));
+    }
+
+    #[ink::test]
+    fn execution_records_executor_and_block() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 1, 1]);
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        contract.vote(proposal_id, 0).unwrap();
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.executed_by, None);
+        assert_eq!(proposal.executed_at, None);
+
+        set_block(proposal.execution_time + 5);
+        contract.grant_role(accounts.eve, Role::Executor).unwrap();
+        ink::env::test::set_caller(accounts.eve);
+        contract.execute_proposal(proposal_id).unwrap();
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.executed_by, Some(accounts.eve));
+        assert_eq!(proposal.executed_at, Some(proposal.execution_time + 5));
+    }
+
+    #[ink::test]
+    fn veto_is_limited_to_the_execution_delay() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 1, 1]);
+        contract.set_guardian(accounts.django).unwrap();
+
+        let vetoed = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let late = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        contract.vote(vetoed, 0).unwrap();
+        contract.vote(late, 0).unwrap();
+        advance_to_voting_end(&contract, vetoed);
+        contract.update_proposal_status(vetoed).unwrap();
+        contract.update_proposal_status(late).unwrap();
+
+        ink::env::test::set_caller(accounts.django);
+        contract.veto_proposal(vetoed).unwrap();
+        assert_eq!(contract.get_proposal(vetoed).unwrap().status, ProposalStatus::Vetoed);
+
+        advance_to_execution_time(&contract, late);
+        assert_eq!(contract.veto_proposal(late), Err(Error::VetoWindowClosed));
+
+        // A vetoed proposal can never be executed
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(contract.execute_proposal(vetoed), Err(Error::ProposalNotReadyForExecution));
+        contract.execute_proposal(late).unwrap();
+    }
+
+    #[ink::test]
+    fn voting_period_can_be_extended_while_open() {
+        use scale::Decode;
+
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 1, 1]);
+        ink::env::test::set_caller(accounts.bob);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let original = contract.get_proposal(proposal_id).unwrap();
+
+        ink::env::test::set_caller(accounts.charlie);
+        assert_eq!(contract.extend_voting_period(proposal_id, 100), Err(Error::NotAuthorized));
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.extend_voting_period(proposal_id, 100).unwrap();
+        let extended = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(extended.voting_end, original.voting_end + 100);
+        assert_eq!(extended.execution_time, original.execution_time + 100);
+
+        let events = ink::env::test::recorded_events();
+        let event = VotingPeriodExtended::decode(&mut &events.last().unwrap().data[..]).unwrap();
+        assert_eq!(event, VotingPeriodExtended { proposal_id, new_voting_end: extended.voting_end });
+
+        // The owner may extend too, up to the total cap
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(
+            contract.extend_voting_period(proposal_id, MAX_VOTING_EXTENSION - 99),
+            Err(Error::ExtensionTooLarge)
+        );
+        contract.extend_voting_period(proposal_id, MAX_VOTING_EXTENSION - 100).unwrap();
+
+        advance_to_voting_end(&contract, proposal_id);
+        assert_eq!(contract.extend_voting_period(proposal_id, 1), Err(Error::VotingPeriodEnded));
+    }
+
+    #[ink::test]
+    fn proposal_text_is_capped_and_metadata_round_trips() {
+        let mut contract = TreasuryGovernance::new();
+        let propose = |contract: &mut TreasuryGovernance, title: String, description: String, hash: Option<[u8; 32]>| {
+            contract.create_proposal(
+                title,
+                description,
+                ProposalType::Governance,
+                default_governance_params(),
+                yes_no_options(),
+                None,
+                hash,
+                None,
+                None,
+                None,
+            )
+        };
+
+        let long_title = "t".repeat(MAX_TITLE_LENGTH + 1);
+        assert_eq!(
+            propose(&mut contract, long_title, "Description".to_string(), None),
+            Err(Error::ProposalTooLarge)
+        );
+        let long_description = "d".repeat(MAX_DESCRIPTION_LENGTH + 1);
+        assert_eq!(
+            propose(&mut contract, "Title".to_string(), long_description, None),
+            Err(Error::ProposalTooLarge)
+        );
+
+        let hash = [7u8; 32];
+        let with_hash = propose(&mut contract, "t".repeat(MAX_TITLE_LENGTH), "Summary".to_string(), Some(hash)).unwrap();
+        assert_eq!(contract.get_proposal(with_hash).unwrap().metadata_hash, Some(hash));
+        let without = propose(&mut contract, "Title".to_string(), "d".repeat(MAX_DESCRIPTION_LENGTH), None).unwrap();
+        assert_eq!(contract.get_proposal(without).unwrap().metadata_hash, None);
+    }
+
+    #[ink::test]
+    fn revoked_vote_leaves_tally_and_allows_fresh_vote() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 3, 4]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        assert_eq!(contract.revoke_vote(proposal_id), Err(Error::NoExistingVote));
+
+        contract.vote(proposal_id, 0).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+
+        ink::env::test::set_caller(accounts.alice);
+        contract.revoke_vote(proposal_id).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts[0], 3);
+        assert_eq!(proposal.total_voters, 1);
+        assert_eq!(proposal.participating_weight, 3);
+        assert!(contract.get_user_vote(proposal_id, accounts.alice).is_err());
+        assert!(contract.get_voter_history(accounts.alice).is_empty());
+
+        contract.vote(proposal_id, 1).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts, vec![3, 2]);
+        assert_eq!(proposal.total_voters, 2);
+
+        advance_to_voting_end(&contract, proposal_id);
+        assert_eq!(contract.revoke_vote(proposal_id), Err(Error::VotingPeriodEnded));
+    }
+
+    #[ink::test]
+    fn revoking_a_carried_vote_frees_delegators() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 3, 4]);
+        contract.delegate(accounts.bob).unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+        assert_eq!(contract.get_carried_by(proposal_id, accounts.alice), Some(accounts.bob));
+
+        contract.revoke_vote(proposal_id).unwrap();
+        assert_eq!(contract.get_carried_by(proposal_id, accounts.alice), None);
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts[0], 0);
+    }
+
+    #[ink::test]
+    fn revoke_releases_delegators_recorded_on_the_vote() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 3, 4]);
+        contract.set_max_delegation_depth(2).unwrap();
+        contract.delegate(accounts.bob).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.delegate(accounts.charlie).unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(proposal_id, 0).unwrap();
+        assert_eq!(contract.get_user_vote(proposal_id, accounts.charlie).unwrap().carried, vec![accounts.bob, accounts.alice]);
+
+        // Bob leaves the chain and the depth drops below alice's hop
+        ink::env::test::set_caller(accounts.bob);
+        contract.undelegate().unwrap();
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_max_delegation_depth(1).unwrap();
+
+        ink::env::test::set_caller(accounts.charlie);
+        contract.revoke_vote(proposal_id).unwrap();
+        assert_eq!(contract.get_carried_by(proposal_id, accounts.bob), None);
+        assert_eq!(contract.get_carried_by(proposal_id, accounts.alice), None);
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 1).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![0, 5]);
+    }
+
+    #[ink::test]
+    fn allowlisted_proposal_restricts_voters_and_quorum() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([10, 10, 80]);
+        let mut params = default_governance_params();
+        params.quorum_threshold = QuorumThreshold::TwentyFive;
+        let proposal_id = contract.create_proposal(
+            "Committee".to_string(),
+            "Subcommittee decision".to_string(),
+            ProposalType::Governance,
+            params,
+            yes_no_options(),
+            None,
+            None,
+            Some(vec![accounts.alice, accounts.bob, accounts.alice]),
+            None,
+            None,
+        ).unwrap();
+        assert_eq!(contract.get_eligible_voters(proposal_id), Some(vec![accounts.alice, accounts.bob]));
+
+        // Quorum is a quarter of the members' 20, not of the full 100
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.snapshot_voters, 2);
+        assert_eq!(proposal.snapshot_total_power, 20);
+        assert_eq!(contract.get_proposal_results(proposal_id).unwrap().quorum_required, 5);
+
+        ink::env::test::set_caller(accounts.charlie);
+        assert_eq!(contract.vote(proposal_id, 1), Err(Error::NotEligibleForProposal));
+        assert_eq!(contract.compute_effective_weight(accounts.charlie, proposal_id), Ok(0));
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 0).unwrap();
+        assert!(contract.has_reached_quorum(proposal_id).unwrap());
+
+        // Unrestricted proposals are unchanged
+        let open_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        assert_eq!(contract.get_eligible_voters(open_id), None);
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote(open_id, 0).unwrap();
+    }
+
+    #[ink::test]
+    fn type_quorum_override_takes_precedence() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([10, 40, 50]);
+        let mut params = default_governance_params();
+        params.quorum_threshold = QuorumThreshold::Five;
+        let technical = create_test_proposal(&mut contract, ProposalType::Technical, params.clone());
+        let other = create_test_proposal(&mut contract, ProposalType::Other, params);
+
+        // Alice's 10% clears the proposals' own 5% quorum
+        contract.vote(technical, 0).unwrap();
+        contract.vote(other, 0).unwrap();
+        assert!(contract.has_reached_quorum(technical).unwrap());
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(
+            contract.set_type_quorum_override(ProposalType::Technical, Some(QuorumThreshold::TwentyFive)),
+            Err(Error::NotAuthorized)
+        );
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_type_quorum_override(ProposalType::Technical, Some(QuorumThreshold::TwentyFive)).unwrap();
+
+        assert_eq!(contract.get_quorum_threshold(technical), Ok(QuorumThreshold::TwentyFive));
+        assert_eq!(contract.get_quorum_threshold(other), Ok(QuorumThreshold::Five));
+        assert!(!contract.has_reached_quorum(technical).unwrap());
+        assert_eq!(contract.get_proposal_results(technical).unwrap().quorum_required, 25);
+
+        advance_to_voting_end(&contract, technical);
+        contract.update_proposal_status(technical).unwrap();
+        contract.update_proposal_status(other).unwrap();
+        let technical = contract.get_proposal(technical).unwrap();
+        assert_eq!(technical.status, ProposalStatus::Rejected);
+        assert_eq!(technical.rejection_reason, Some(RejectionReason::QuorumNotReached));
+        assert_eq!(contract.get_proposal(other).unwrap().status, ProposalStatus::Passed);
+    }
+
+    #[ink::test]
+    fn quorum_setters_reject_out_of_range_thresholds() {
+        let mut contract = weighted_trio([10, 40, 50]);
+        for bad in [QuorumThreshold::Custom(0), QuorumThreshold::Custom(500)] {
+            assert_eq!(
+                contract.set_type_quorum_override(ProposalType::Technical, Some(bad.clone())),
+                Err(Error::InvalidProposal)
+            );
+            assert_eq!(
+                contract.set_type_min_quorum(ProposalType::Technical, Some(bad.clone())),
+                Err(Error::InvalidProposal)
+            );
+            let mut params = default_governance_params();
+            params.quorum_threshold = bad;
+            assert_eq!(contract.set_default_governance_params(params), Err(Error::InvalidProposal));
+        }
+
+        // Nothing was stored by the rejected calls
+        let mut params = default_governance_params();
+        params.quorum_threshold = QuorumThreshold::Five;
+        let id = create_test_proposal(&mut contract, ProposalType::Technical, params);
+        assert_eq!(contract.get_quorum_threshold(id), Ok(QuorumThreshold::Five));
+
+        // Bounds are inclusive
+        contract.set_type_quorum_override(ProposalType::Technical, Some(QuorumThreshold::Custom(100))).unwrap();
+        contract.set_type_min_quorum(ProposalType::Technical, Some(QuorumThreshold::Custom(1))).unwrap();
+        let mut params = default_governance_params();
+        params.quorum_threshold = QuorumThreshold::Custom(100);
+        contract.set_default_governance_params(params).unwrap();
+        assert_eq!(contract.get_quorum_threshold(id), Ok(QuorumThreshold::Custom(100)));
+    }
+
+    #[ink::test]
+    fn execution_call_is_stored_and_failures_surface() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 1, 1]);
+        let call = ExecutionCall {
+            target: accounts.django,
+            selector: ink::selector_bytes!("set_value"),
+            input: scale::Encode::encode(&42u32),
+            gas_limit: 1_000_000,
+        };
+
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        let proposal_id = contract.create_proposal(
+            "Call".to_string(),
+            "Call another contract".to_string(),
+            ProposalType::Technical,
+            params,
+            yes_no_options(),
+            None,
+            None,
+            None,
+            Some(call.clone()),
+            None,
+        ).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().execution_call, Some(call));
+
+        contract.vote(proposal_id, 0).unwrap();
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+
+        // The target holds no contract, so the call cannot succeed
+        assert_eq!(contract.execute_proposal(proposal_id), Err(Error::ExecutionCallFailed));
+    }
+
+    #[ink::test]
+    fn quadratic_tally_is_square_root_of_credits() {
+        let accounts = ink::env::test::default_accounts();
+        let voters = [accounts.alice, accounts.bob, accounts.charlie];
+        let inputs = [4u128, 9, 16];
+
+        // The same amounts serve as weights on a linear proposal and as
+        // credits on a quadratic one
+        let mut contract = weighted_trio(inputs);
+        for (voter, &amount) in voters.iter().zip(&inputs) {
+            contract.set_credits(*voter, amount).unwrap();
+        }
+        let linear_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let mut params = default_governance_params();
+        params.voting_mode = VotingMode::Quadratic;
+        let quadratic_id = create_test_proposal(&mut contract, ProposalType::Governance, params);
+
+        assert_eq!(contract.vote(quadratic_id, 0), Err(Error::InvalidVotingMode));
+        assert_eq!(contract.vote_quadratic(quadratic_id, 0, 5), Err(Error::InsufficientCredits));
+        assert_eq!(contract.vote_quadratic(linear_id, 0, 4), Err(Error::InvalidVotingMode));
+
+        for (voter, (&amount, option)) in voters.iter().zip(inputs.iter().zip([0, 1, 1])) {
+            ink::env::test::set_caller(*voter);
+            contract.vote(linear_id, option).unwrap();
+            contract.vote_quadratic(quadratic_id, option, amount).unwrap();
+        }
+
+        assert_eq!(contract.get_proposal(linear_id).unwrap().vote_counts, vec![4, 25]);
+        assert_eq!(contract.get_proposal(quadratic_id).unwrap().vote_counts, vec![2, 7]);
+        assert_eq!(contract.get_credits(accounts.bob), 0);
+    }
+
+    #[ink::test]
+    fn quadratic_votes_round_down_and_keep_unspent_credits() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 1, 1]);
+        let mut params = default_governance_params();
+        params.voting_mode = VotingMode::Quadratic;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        contract.set_credits(accounts.alice, 100).unwrap();
+
+        contract.vote_quadratic(proposal_id, 0, 15).unwrap();
+        assert_eq!(contract.get_user_vote(proposal_id, accounts.alice).unwrap().weight, 3);
+        assert_eq!(contract.get_credits(accounts.alice), 85);
+    }
+
+    #[ink::test]
+    fn quadratic_weight_reports_square_root_of_credits() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 1, 1]);
+        let mut params = default_governance_params();
+        params.voting_mode = VotingMode::Quadratic;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        contract.set_credits(accounts.alice, 50).unwrap();
+
+        assert_eq!(contract.compute_effective_weight(accounts.alice, proposal_id), Ok(7));
+        assert_eq!(contract.dry_run_vote_quadratic(proposal_id, 0, 50, accounts.alice), Ok(7));
+        contract.vote_quadratic(proposal_id, 0, 50).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![7, 0]);
+        assert_eq!(contract.compute_effective_weight(accounts.alice, proposal_id), Ok(0));
+    }
+
+    #[ink::test]
+    fn dry_run_vote_quadratic_mirrors_vote_quadratic() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 1, 1]);
+        let mut params = default_governance_params();
+        params.voting_mode = VotingMode::Quadratic;
+        let quadratic = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        let linear = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        contract.set_credits(accounts.alice, 20).unwrap();
+
+        // A plain vote cannot be cast on a quadratic proposal, nor previewed
+        assert_eq!(contract.dry_run_vote(quadratic, 0, accounts.alice), Err(Error::InvalidVotingMode));
+        assert_eq!(contract.vote(quadratic, 0), Err(Error::InvalidVotingMode));
+
+        assert_eq!(
+            contract.dry_run_vote_quadratic(linear, 0, 9, accounts.alice),
+            Err(Error::InvalidVotingMode)
+        );
+        assert_eq!(
+            contract.dry_run_vote_quadratic(quadratic, 0, 21, accounts.alice),
+            Err(Error::InsufficientCredits)
+        );
+        assert_eq!(contract.vote_quadratic(quadratic, 0, 21), Err(Error::InsufficientCredits));
+        assert_eq!(
+            contract.dry_run_vote_quadratic(quadratic, 5, 9, accounts.alice),
+            Err(Error::InvalidOptionIndex)
+        );
+
+        // Dry runs spend nothing, and the preview is what the vote records
+        assert_eq!(contract.dry_run_vote_quadratic(quadratic, 0, 15, accounts.alice), Ok(3));
+        assert_eq!(contract.get_credits(accounts.alice), 20);
+        contract.vote_quadratic(quadratic, 0, 15).unwrap();
+        assert_eq!(contract.get_user_vote(quadratic, accounts.alice).unwrap().weight, 3);
+        assert_eq!(
+            contract.dry_run_vote_quadratic(quadratic, 1, 1, accounts.alice),
+            Err(Error::AlreadyVoted)
+        );
+    }
+
+    #[ink::test]
+    fn delegators_vote_directly_on_quadratic_proposals() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 1, 1]);
+        contract.set_credits(accounts.bob, 9).unwrap();
+        contract.set_credits(accounts.alice, 16).unwrap();
+        let mut params = default_governance_params();
+        params.voting_mode = VotingMode::Quadratic;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        ink::env::test::set_caller(accounts.bob);
+        contract.delegate(accounts.alice).unwrap();
+
+        // The delegate's credits carry none of the delegator's weight
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(contract.compute_effective_weight(accounts.bob, proposal_id), Ok(3));
+        contract.vote_quadratic(proposal_id, 0, 16).unwrap();
+        assert_eq!(contract.get_carried_by(proposal_id, accounts.bob), None);
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote_quadratic(proposal_id, 1, 9).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts, vec![4, 3]);
+        assert_eq!(proposal.participating_weight, 2);
+    }
+
+    #[ink::test]
+    fn revoking_a_quadratic_vote_refunds_credits() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 1, 1]);
+        let mut params = default_governance_params();
+        params.voting_mode = VotingMode::Quadratic;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        contract.set_credits(accounts.alice, 100).unwrap();
+
+        contract.vote_quadratic(proposal_id, 0, 64).unwrap();
+        assert_eq!(contract.get_user_vote(proposal_id, accounts.alice).unwrap().credits_spent, 64);
+        assert_eq!(contract.get_credits(accounts.alice), 36);
+
+        // Changing moves the votes and keeps the spend on record
+        contract.change_vote(proposal_id, 1).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![0, 8]);
+        assert_eq!(contract.get_credits(accounts.alice), 36);
+
+        contract.revoke_vote(proposal_id).unwrap();
+        assert_eq!(contract.get_credits(accounts.alice), 100);
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![0, 0]);
+        contract.vote_quadratic(proposal_id, 0, 100).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![10, 0]);
+    }
+
+    #[ink::test]
+    fn quadratic_quorum_counts_voter_weight_and_waits_for_voting_end() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([100, 100, 100]);
+        contract.set_resolve_on_vote(true).unwrap();
+        let mut params = default_governance_params();
+        params.voting_mode = VotingMode::Quadratic;
+        params.quorum_threshold = QuorumThreshold::Custom(50);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        contract.set_credits(accounts.alice, 1_000_000).unwrap();
+
+        // A large credit spend buys tally, not participation
+        contract.vote_quadratic(proposal_id, 1, 1_000_000).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts, vec![0, 1000]);
+        assert_eq!(proposal.participating_weight, 100);
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Active);
+        assert_eq!(contract.has_reached_quorum(proposal_id), Ok(false));
+
+        // A second voter's weight brings participation to half
+        contract.set_credits(accounts.bob, 1).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote_quadratic(proposal_id, 0, 1).unwrap();
+        assert_eq!(contract.has_reached_quorum(proposal_id), Ok(true));
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Active);
+
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+    }
+
+    #[ink::test]
+    fn ranked_choice_runoff_overturns_plurality() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([5, 3, 4]);
+        let mut params = default_governance_params();
+        params.voting_mode = VotingMode::RankedChoice;
+        let proposal_id = contract.create_proposal(
+            "Pick one".to_string(),
+            "Three way choice".to_string(),
+            ProposalType::Other,
+            params,
+            VotingOptions { options: vec!["A".to_string(), "B".to_string(), "C".to_string()] },
+            None,
+            None,
+            None,
+            None,
+            None,
+        ).unwrap();
+
+        assert_eq!(contract.vote_ranked(proposal_id, vec![1, 1]), Err(Error::InvalidOptionIndex));
+        contract.vote_ranked(proposal_id, vec![0]).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote_ranked(proposal_id, vec![1, 2]).unwrap();
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote_ranked(proposal_id, vec![2, 1]).unwrap();
+
+        // A leads on first preferences without a majority
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts, vec![5, 3, 4]);
+        assert_eq!(contract.get_user_vote(proposal_id, accounts.bob).unwrap().selections, vec![1, 2]);
+
+        // B is eliminated and its 3 move to C, which then holds 7 of 12
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+        assert_eq!(contract.get_winning_option(proposal_id), Ok(Some(("C".to_string(), 4))));
+    }
+
+    #[ink::test]
+    fn ranked_choice_exhausted_ballots_and_ties() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 1, 2]);
+        let mut params = default_governance_params();
+        params.voting_mode = VotingMode::RankedChoice;
+        let proposal_id = contract.create_proposal(
+            "Pick one".to_string(),
+            "Three way choice".to_string(),
+            ProposalType::Other,
+            params,
+            VotingOptions { options: vec!["A".to_string(), "B".to_string(), "C".to_string()] },
+            None,
+            None,
+            None,
+            None,
+            None,
+        ).unwrap();
+        assert_eq!(contract.vote_ranked(99, vec![0]), Err(Error::ProposalNotFound));
+
+        // Bob ranks only B; once B is out his ballot is exhausted, leaving
+        // A and C level
+        contract.vote_ranked(proposal_id, vec![0]).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote_ranked(proposal_id, vec![1]).unwrap();
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote_ranked(proposal_id, vec![2]).unwrap();
+
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+        assert_eq!(proposal.rejection_reason, Some(RejectionReason::Tie));
+        assert_eq!(contract.get_winning_option(proposal_id), Ok(None));
+    }
+
+    #[ink::test]
+    fn conviction_lock_multiplies_weight_up_to_cap() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 1, 1]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+
+        contract.vote_with_conviction(proposal_id, 0, CONVICTION_PERIOD - 1).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote_with_conviction(proposal_id, 1, 2 * CONVICTION_PERIOD).unwrap();
+        ink::env::test::set_caller(accounts.charlie);
+        // Locking past the point the multiplier stops growing is refused
+        assert_eq!(
+            contract.vote_with_conviction(proposal_id, 0, MAX_CONVICTION_LOCK + 1),
+            Err(Error::ConvictionLockTooLong)
+        );
+        assert_eq!(contract.vote_with_conviction(proposal_id, 0, u32::MAX), Err(Error::ConvictionLockTooLong));
+        contract.vote_with_conviction(proposal_id, 0, MAX_CONVICTION_LOCK).unwrap();
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts, vec![1 + MAX_CONVICTION_MULTIPLIER, 3]);
+        let vote = contract.get_user_vote(proposal_id, accounts.bob).unwrap();
+        assert_eq!(vote.weight, 3);
+        assert_eq!(vote.lock_until, proposal.created_at + 2 * CONVICTION_PERIOD);
+        let vote = contract.get_user_vote(proposal_id, accounts.charlie).unwrap();
+        assert_eq!(vote.lock_until, proposal.created_at + MAX_CONVICTION_LOCK);
+    }
+
+    #[ink::test]
+    fn conviction_lock_released_when_proposal_fails() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([5, 2, 5]);
+        let cancelled = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let params = GovernanceParameters {
+            quorum_threshold: QuorumThreshold::TwentyFive,
+            ..default_governance_params()
+        };
+        let rejected = create_test_proposal(&mut contract, ProposalType::Governance, params);
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote_with_conviction(cancelled, 0, MAX_CONVICTION_LOCK).unwrap();
+        contract.vote_with_conviction(rejected, 1, MAX_CONVICTION_LOCK).unwrap();
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(contract.set_voting_power(accounts.bob, 1), Err(Error::VoteLocked));
+
+        // Cancelling one proposal leaves the other lock in force
+        contract.cancel_proposal(cancelled).unwrap();
+        assert_eq!(contract.set_voting_power(accounts.bob, 1), Err(Error::VoteLocked));
+
+        // Once Bob's vote alone misses quorum there, nothing holds the weight
+        advance_to_voting_end(&contract, rejected);
+        contract.update_proposal_status(rejected).unwrap();
+        assert_eq!(contract.get_proposal(rejected).unwrap().status, ProposalStatus::Rejected);
+        contract.set_voting_power(accounts.bob, 1).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.deregister_voter().unwrap();
+    }
+
+    #[ink::test]
+    fn maximal_tallies_do_not_overflow() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([u128::MAX, u128::MAX, 1]);
+        contract.set_abstain_majority_rejects(true).unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+
+        contract.vote(proposal_id, 0).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(proposal_id, 1).unwrap();
+        assert_eq!(contract.get_proposal_results(proposal_id).unwrap().total_votes, u128::MAX);
+
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+        assert_eq!(proposal.rejection_reason, Some(RejectionReason::Tie));
+        assert_eq!(contract.get_proposal_results(proposal_id).unwrap().total_votes, u128::MAX);
+    }
+
+    #[ink::test]
+    fn total_votes_sum_the_tallies() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 1, 1]);
+        let conviction = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let approval = create_approval_proposal(&mut contract, QuorumThreshold::Ten);
+
+        // Conviction multiplies the tallied weight but not the participation
+        contract.vote_with_conviction(conviction, 0, 2 * CONVICTION_PERIOD).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.abstain(conviction).unwrap();
+        let proposal = contract.get_proposal(conviction).unwrap();
+        assert_eq!(proposal.participating_weight, 2);
+        assert_eq!(contract.get_total_votes(conviction), Ok(4));
+
+        // Each approval selection is counted once
+        contract.vote_approval(approval, vec![0, 1, 2]).unwrap();
+        ink::env::test::set_caller(accounts.charlie);
+        contract.vote_approval(approval, vec![1]).unwrap();
+        assert_eq!(contract.get_proposal(approval).unwrap().vote_counts, vec![1, 2, 1]);
+        assert_eq!(contract.get_total_votes(approval), Ok(4));
+    }
+
+    #[ink::test]
+    fn conviction_vote_cannot_be_revoked_until_lock_ends() {
+        let mut contract = weighted_trio([1, 1, 1]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+
+        contract.vote_with_conviction(proposal_id, 0, 10).unwrap();
+        let lock_until = contract.get_user_vote(proposal_id, ink::env::test::default_accounts().alice).unwrap().lock_until;
+        set_block(lock_until - 1);
+        assert_eq!(contract.revoke_vote(proposal_id), Err(Error::VoteLocked));
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts[0], 1);
+
+        set_block(lock_until);
+        contract.revoke_vote(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts[0], 0);
+    }
+
+    #[ink::test]
+    fn passed_proposals_execute_in_pass_order() {
+        let mut contract = weighted_trio([2, 1, 1]);
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        let ids: Vec<u32> = (0..3)
+            .map(|_| create_test_proposal(&mut contract, ProposalType::Governance, params.clone()))
+            .collect();
+        for &id in &ids {
+            contract.vote(id, 0).unwrap();
+        }
+
+        advance_to_voting_end(&contract, ids[0]);
+        for id in [ids[2], ids[0], ids[1]] {
+            contract.update_proposal_status(id).unwrap();
+        }
+        assert_eq!(contract.get_execution_queue(), vec![ids[2], ids[0], ids[1]]);
+
+        assert_eq!(contract.execute_proposal(ids[0]), Err(Error::NotNextInQueue));
+        assert_eq!(contract.execute_proposal(ids[1]), Err(Error::NotNextInQueue));
+        contract.execute_proposal(ids[2]).unwrap();
+        assert_eq!(contract.execute_proposal(ids[1]), Err(Error::NotNextInQueue));
+        contract.execute_proposal(ids[0]).unwrap();
+        contract.execute_proposal(ids[1]).unwrap();
+        assert!(contract.get_execution_queue().is_empty());
+    }
+
+    /// Create an immediately executable governance proposal depending on another
+    fn create_dependent_proposal(contract: &mut TreasuryGovernance, depends_on: u32) -> Result<u32> {
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        contract.create_proposal(
+            "Fund project".to_string(),
+            "Needs the budget approved first".to_string(),
+            ProposalType::Governance,
+            params,
+            yes_no_options(),
+            None,
+            None,
+            None,
+            None,
+            Some(depends_on),
+        )
+    }
+
+    #[ink::test]
+    fn dependent_proposal_executes_after_dependency() {
+        let mut contract = weighted_trio([2, 1, 1]);
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        let budget = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        assert_eq!(create_dependent_proposal(&mut contract, 99), Err(Error::ProposalNotFound));
+        let funding = create_dependent_proposal(&mut contract, budget).unwrap();
+        assert_eq!(contract.get_proposal(funding).unwrap().depends_on, Some(budget));
+
+        contract.vote(budget, 0).unwrap();
+        contract.vote(funding, 0).unwrap();
+        advance_to_voting_end(&contract, budget);
+        contract.update_proposal_status(budget).unwrap();
+        contract.update_proposal_status(funding).unwrap();
+        assert_eq!(contract.get_execution_queue(), vec![budget]);
+
+        assert_eq!(contract.execute_proposal(funding), Err(Error::DependencyNotExecuted));
+        contract.execute_proposal(budget).unwrap();
+        assert_eq!(contract.get_execution_queue(), vec![funding]);
+        contract.execute_proposal(funding).unwrap();
+        assert_eq!(contract.get_proposal(funding).unwrap().status, ProposalStatus::Executed);
+    }
+
+    #[ink::test]
+    fn rejected_dependency_blocks_execution() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 1, 1]);
+        let budget = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let funding = create_dependent_proposal(&mut contract, budget).unwrap();
+        let unrelated = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+
+        // Budget ties and is rejected; funding and the unrelated proposal pass
+        contract.vote(budget, 0).unwrap();
+        contract.vote(funding, 0).unwrap();
+        contract.vote(unrelated, 0).unwrap();
+        for voter in [accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.vote(budget, 1).unwrap();
+        }
+        ink::env::test::set_caller(accounts.alice);
+        advance_to_voting_end(&contract, budget);
+
+        // Funding passes first but waits outside the queue for its dependency
+        contract.update_proposal_status(funding).unwrap();
+        assert_eq!(contract.get_proposal(funding).unwrap().status, ProposalStatus::Passed);
+        assert!(contract.get_execution_queue().is_empty());
+        assert_eq!(contract.execute_proposal(funding), Err(Error::DependencyNotExecuted));
+
+        // Rejecting the budget expires funding, which never blocks the queue
+        contract.update_proposal_status(budget).unwrap();
+        contract.update_proposal_status(unrelated).unwrap();
+        assert_eq!(contract.get_proposal(budget).unwrap().status, ProposalStatus::Rejected);
+        assert_eq!(contract.get_proposal(funding).unwrap().status, ProposalStatus::Expired);
+        assert_eq!(contract.get_execution_queue(), vec![unrelated]);
+        assert_eq!(create_dependent_proposal(&mut contract, budget), Err(Error::DependencyNotExecuted));
+
+        advance_to_execution_time(&contract, unrelated);
+        assert_eq!(contract.execute_proposal(unrelated), Ok(()));
+        assert!(contract.get_execution_queue().is_empty());
+    }
+
+    #[ink::test]
+    fn voting_period_floor_rejects_shorter_periods() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 1, 1]);
+        let seven_days = VotingPeriod::SevenDays.to_blocks();
+
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.set_min_voting_period_blocks(seven_days), Err(Error::NotAuthorized));
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_min_voting_period_blocks(seven_days).unwrap();
+        assert_eq!(contract.get_min_voting_period_blocks(), seven_days);
+
+        let propose = |contract: &mut TreasuryGovernance, voting_period| {
+            let mut params = default_governance_params();
+            params.voting_period = voting_period;
+            contract.create_proposal(
+                "Test Proposal".to_string(),
+                "Test Description".to_string(),
+                ProposalType::Governance,
+                params,
+                yes_no_options(),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+        assert_eq!(propose(&mut contract, VotingPeriod::ThreeDays), Err(Error::InvalidVotingPeriod));
+        assert_eq!(propose(&mut contract, VotingPeriod::Custom(seven_days - 1)), Err(Error::InvalidVotingPeriod));
+        assert!(propose(&mut contract, VotingPeriod::SevenDays).is_ok());
+        assert!(propose(&mut contract, VotingPeriod::FourteenDays).is_ok());
+
+        contract.set_min_voting_period_blocks(0).unwrap();
+        assert!(propose(&mut contract, VotingPeriod::ThreeDays).is_ok());
+    }
+
+    #[ink::test]
+    fn effective_status_reflects_elapsed_voting_period() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 1, 1]);
+        let passing = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let tied = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let silent = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        contract.vote(passing, 0).unwrap();
+        contract.vote(tied, 0).unwrap();
+        for voter in [accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.vote(tied, 1).unwrap();
+        }
+        assert_eq!(contract.get_effective_status(passing), Ok(ProposalStatus::Active));
+
+        advance_to_voting_end(&contract, passing);
+        for (id, expected) in [
+            (passing, ProposalStatus::Passed),
+            (tied, ProposalStatus::Rejected),
+            (silent, ProposalStatus::Expired),
+        ] {
+            assert_eq!(contract.get_proposal(id).unwrap().status, ProposalStatus::Active);
+            assert_eq!(contract.get_effective_status(id), Ok(expected.clone()));
+            contract.update_proposal_status(id).unwrap();
+            assert_eq!(contract.get_proposal(id).unwrap().status, expected);
+        }
+        assert_eq!(contract.get_effective_status(99), Err(Error::ProposalNotFound));
+    }
+
+    #[ink::test]
+    fn stats_report_votes_cast_and_average_participation() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 1, 1]);
+        let full = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let half = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let open = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+
+        for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.vote(full, 0).unwrap();
+        }
+        ink::env::test::set_caller(accounts.alice);
+        contract.vote(half, 0).unwrap();
+        contract.vote(open, 1).unwrap();
+        advance_to_voting_end(&contract, full);
+        contract.update_proposal_status(full).unwrap();
+        contract.update_proposal_status(half).unwrap();
+
+        // The unresolved proposal counts toward votes cast but not the average
+        let stats = contract.get_stats();
+        assert_eq!(stats.total_votes_cast, 8);
+        assert_eq!(stats.average_participation_bps, 7_500);
+    }
+
+    #[ink::test]
+    fn proposer_vote_rule_covers_changes_and_can_be_lifted() {
+        let mut contract = weighted_trio([1, 1, 1]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        contract.vote(proposal_id, 0).unwrap();
+
+        // An earlier vote can no longer be changed, only revoked
+        contract.set_allow_proposer_vote(false).unwrap();
+        assert_eq!(contract.change_vote(proposal_id, 1), Err(Error::ProposerCannotVote));
+        contract.revoke_vote(proposal_id).unwrap();
+        assert_eq!(contract.vote(proposal_id, 1), Err(Error::ProposerCannotVote));
+
+        contract.set_allow_proposer_vote(true).unwrap();
+        contract.vote(proposal_id, 1).unwrap();
+        contract.change_vote(proposal_id, 0).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![1, 0]);
+    }
+
+    #[ink::test]
+    fn custom_quorum_percentage() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 1, 1]);
+        let mut params = default_governance_params();
+        params.quorum_threshold = QuorumThreshold::Custom(50);
+        let reached = create_test_proposal(&mut contract, ProposalType::Governance, params.clone());
+        let missed = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        assert_eq!(contract.get_quorum_threshold(reached), Ok(QuorumThreshold::Custom(50)));
+
+        // Half of the weight of 4 must take part
+        contract.vote(reached, 0).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.vote(missed, 0).unwrap();
+        advance_to_voting_end(&contract, reached);
+        contract.update_proposal_status(reached).unwrap();
+        contract.update_proposal_status(missed).unwrap();
+        assert_eq!(contract.get_proposal(reached).unwrap().status, ProposalStatus::Passed);
+        let missed = contract.get_proposal(missed).unwrap();
+        assert_eq!(missed.status, ProposalStatus::Rejected);
+        assert_eq!(missed.rejection_reason, Some(RejectionReason::QuorumNotReached));
+
+        ink::env::test::set_caller(accounts.alice);
+        for percentage in [0, 101] {
+            let mut params = default_governance_params();
+            params.quorum_threshold = QuorumThreshold::Custom(percentage);
+            let result = contract.create_proposal(
+                "Test Proposal".to_string(),
+                "Test Description".to_string(),
+                ProposalType::Governance,
+                params,
+                yes_no_options(),
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            assert_eq!(result, Err(Error::InvalidProposal));
+        }
+    }
+
+    #[ink::test]
+    fn approval_fraction_counts_abstentions() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 1, 1]);
+        let mut params = default_governance_params();
+        params.min_approval_bps = 6_000;
+        let contested = create_test_proposal(&mut contract, ProposalType::Governance, params.clone());
+        let clear = create_test_proposal(&mut contract, ProposalType::Governance, params.clone());
+
+        // Approvals of 2 against 1 rejection: 66% without the abstention, 50% with it
+        for id in [contested, clear] {
+            contract.vote(id, 0).unwrap();
+            ink::env::test::set_caller(accounts.bob);
+            contract.vote(id, 1).unwrap();
+            ink::env::test::set_caller(accounts.alice);
+        }
+        ink::env::test::set_caller(accounts.charlie);
+        contract.abstain(contested).unwrap();
+
+        advance_to_voting_end(&contract, contested);
+        contract.update_proposal_status(contested).unwrap();
+        contract.update_proposal_status(clear).unwrap();
+        let contested = contract.get_proposal(contested).unwrap();
+        assert_eq!(contract.get_quorum_shortfall(contested.id), Ok(0));
+        assert_eq!(contested.status, ProposalStatus::Rejected);
+        assert_eq!(contested.rejection_reason, Some(RejectionReason::ApprovalThresholdNotMet));
+        assert_eq!(contract.get_proposal(clear).unwrap().status, ProposalStatus::Passed);
+
+        ink::env::test::set_caller(accounts.alice);
+        params.min_approval_bps = 10_001;
+        let result = contract.create_proposal(
+            "Test Proposal".to_string(),
+            "Test Description".to_string(),
+            ProposalType::Governance,
+            params,
+            yes_no_options(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(result, Err(Error::InvalidProposal));
+    }
+
+    #[ink::test]
+    fn reentrancy_guard_is_released_after_each_execution() {
+        // A target calling back in needs a second deployed contract, which
+        // the off-chain environment cannot host; this covers the guard's
+        // release on both failed and successful executions.
+        let mut contract = weighted_trio([2, 1, 1]);
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Immediately;
+        let first = create_test_proposal(&mut contract, ProposalType::Governance, params.clone());
+        let second = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        contract.vote(first, 0).unwrap();
+        contract.vote(second, 0).unwrap();
+
+        assert_eq!(contract.execute_proposal(first), Err(Error::ProposalNotReadyForExecution));
+        advance_to_voting_end(&contract, first);
+        contract.update_proposal_status(first).unwrap();
+        contract.update_proposal_status(second).unwrap();
+
+        contract.execute_proposal(first).unwrap();
+        assert_eq!(contract.execute_proposal(first), Err(Error::ProposalNotReadyForExecution));
+        contract.execute_proposal(second).unwrap();
+        assert_eq!(contract.get_proposal(second).unwrap().status, ProposalStatus::Executed);
+    }
+
+    #[ink::test]
+    fn custom_execution_delay() {
+        let mut contract = weighted_trio([2, 1, 1]);
+        let twelve_hours = 12 * 60 * 10;
+        let mut params = default_governance_params();
+        params.execution_delay = ExecutionDelay::Custom(twelve_hours);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Treasury, params.clone());
+        contract.vote(proposal_id, 0).unwrap();
+
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.execution_time, proposal.voting_end + twelve_hours);
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.execute_proposal(proposal_id), Err(Error::ExecutionDelayNotMet));
+        advance_to_execution_time(&contract, proposal_id);
+        contract.execute_proposal(proposal_id).unwrap();
+
+        params.execution_delay = ExecutionDelay::Custom(MAX_CUSTOM_EXECUTION_DELAY + 1);
+        let result = contract.create_proposal(
+            "Test Proposal".to_string(),
+            "Test Description".to_string(),
+            ProposalType::Treasury,
+            params,
+            yes_no_options(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(result, Err(Error::InvalidExecutionDelay));
+    }
+
+    #[ink::test]
+    fn countdowns_to_voting_end_and_execution() {
+        let mut contract = weighted_trio([1, 1, 1]);
+        let params = default_governance_params();
+        let voting_blocks = params.voting_period.to_blocks();
+        let delay_blocks = params.execution_delay.to_blocks();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, params);
+
+        assert_eq!(contract.blocks_until_voting_end(proposal_id), Ok(voting_blocks));
+        assert_eq!(contract.blocks_until_executable(proposal_id), Ok(voting_blocks + delay_blocks));
+
+        advance_to_voting_end(&contract, proposal_id);
+        assert_eq!(contract.blocks_until_voting_end(proposal_id), Ok(0));
+        assert_eq!(contract.blocks_until_executable(proposal_id), Ok(delay_blocks - 1));
+
+        advance_to_execution_time(&contract, proposal_id);
+        assert_eq!(contract.blocks_until_executable(proposal_id), Ok(0));
+        assert_eq!(contract.blocks_until_voting_end(99), Err(Error::ProposalNotFound));
+    }
+
+    #[ink::test]
+    fn proposals_need_minimum_electorate() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_min_electorate(2).unwrap();
+        assert_eq!(contract.get_min_electorate(), 2);
+        let propose = |contract: &mut TreasuryGovernance| {
+            contract.create_proposal(
+                "Test Proposal".to_string(),
+                "Test Description".to_string(),
+                ProposalType::Governance,
+                default_governance_params(),
+                yes_no_options(),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+
+        // No voters, then a one-person electorate
+        assert_eq!(propose(&mut contract), Err(Error::InsufficientElectorate));
+        contract.register_voter().unwrap();
+        assert_eq!(propose(&mut contract), Err(Error::InsufficientElectorate));
+
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+        assert!(propose(&mut contract).is_ok());
+
+        // Blacklisted voters do not count
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_blacklisted(accounts.bob, true).unwrap();
+        assert_eq!(propose(&mut contract), Err(Error::InsufficientElectorate));
+    }
+
+    #[ink::test]
+    fn refresh_all_statuses_finalizes_ended_proposals() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([2, 1, 1]);
+        let passing = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let tied = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        let silent = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        contract.vote(passing, 0).unwrap();
+        contract.vote(tied, 0).unwrap();
+        for voter in [accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.vote(tied, 1).unwrap();
+        }
+        assert_eq!(contract.refresh_all_statuses(0), Ok((0, None)));
+
+        advance_to_voting_end(&contract, passing);
+        let mut params = default_governance_params();
+        params.voting_period = VotingPeriod::ThirtyDays;
+        let open = create_test_proposal(&mut contract, ProposalType::Governance, params);
+
+        assert_eq!(contract.refresh_all_statuses(0), Ok((3, None)));
+        assert_eq!(contract.get_proposal(passing).unwrap().status, ProposalStatus::Passed);
+        assert_eq!(contract.get_proposal(tied).unwrap().status, ProposalStatus::Rejected);
+        assert_eq!(contract.get_proposal(silent).unwrap().status, ProposalStatus::Expired);
+        assert_eq!(contract.get_proposal(open).unwrap().status, ProposalStatus::Active);
+        assert_eq!(contract.get_execution_queue(), vec![passing]);
+        assert_eq!(contract.refresh_all_statuses(0), Ok((0, None)));
+    }
+
+    #[ink::test]
+    fn refresh_all_statuses_expires_passed_proposals_with_closed_windows() {
+        let mut contract = weighted_trio([2, 1, 1]);
+        let mut params = default_governance_params();
+        params.execution_window_blocks = 10;
+        let stale = create_test_proposal(&mut contract, ProposalType::Governance, params);
+        let fresh = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        contract.vote(stale, 0).unwrap();
+        contract.vote(fresh, 0).unwrap();
+        advance_to_voting_end(&contract, stale);
+        assert_eq!(contract.refresh_all_statuses(0), Ok((2, None)));
+        assert_eq!(contract.get_execution_queue(), vec![stale, fresh]);
+
+        // Inside the window the head stays queued
+        advance_to_execution_time(&contract, stale);
+        assert_eq!(contract.refresh_all_statuses(0), Ok((0, None)));
+
+        let execution_time = contract.get_proposal(stale).unwrap().execution_time;
+        set_block(execution_time + 11);
+        assert_eq!(contract.refresh_all_statuses(0), Ok((1, None)));
+        assert_eq!(contract.get_proposal(stale).unwrap().status, ProposalStatus::Expired);
+        assert_eq!(contract.get_execution_queue(), vec![fresh]);
+        contract.execute_proposal(fresh).unwrap();
+    }
+
+    #[ink::test]
+    fn refresh_all_statuses_scans_a_bounded_batch() {
+        let mut contract = weighted_trio([1, 1, 1]);
+        let last = (0..=MAX_REFRESH_BATCH)
+            .map(|_| create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params()))
+            .last()
+            .unwrap();
+        advance_to_voting_end(&contract, last);
+
+        // The first call stops after a full batch and hands back a cursor
+        assert_eq!(contract.refresh_all_statuses(0), Ok((MAX_REFRESH_BATCH, Some(MAX_REFRESH_BATCH))));
+        assert_eq!(contract.get_proposal(last).unwrap().status, ProposalStatus::Active);
+        assert_eq!(contract.refresh_all_statuses(MAX_REFRESH_BATCH), Ok((1, None)));
+        assert_eq!(contract.get_proposal(last).unwrap().status, ProposalStatus::Expired);
+        assert_eq!(contract.refresh_all_statuses(MAX_REFRESH_BATCH.saturating_add(5)), Ok((0, None)));
+    }
+
+    #[ink::test]
+    fn conviction_does_not_count_toward_quorum_or_early_resolution() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([4, 3, 3]);
+        contract.set_resolve_on_vote(true).unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+
+        contract.vote_with_conviction(proposal_id, 0, 5 * CONVICTION_PERIOD).unwrap();
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts, vec![24, 0]);
+        assert_eq!(proposal.participating_weight, 4);
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Active);
+
+        // The remaining power can still vote, and at full conviction outweighs the lead
+        for voter in [accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.vote_with_conviction(proposal_id, 1, 5 * CONVICTION_PERIOD).unwrap();
+        }
+        let proposal = contract.get_proposal(proposal_id).unwrap();
+        assert_eq!(proposal.vote_counts, vec![24, 36]);
+        assert_eq!(proposal.participating_weight, 10);
+    }
+
+    #[ink::test]
+    fn conviction_vote_cannot_be_changed_until_lock_ends() {
+        let mut contract = weighted_trio([1, 1, 1]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+
+        contract.vote_with_conviction(proposal_id, 0, CONVICTION_PERIOD).unwrap();
+        let lock_until = contract.get_user_vote(proposal_id, ink::env::test::default_accounts().alice).unwrap().lock_until;
+        assert_eq!(contract.change_vote(proposal_id, 1), Err(Error::VoteLocked));
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![2, 0]);
+
+        set_block(lock_until);
+        contract.change_vote(proposal_id, 1).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![0, 2]);
+    }
+
+    #[ink::test]
+    fn change_vote_requires_continued_eligibility() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 1, 1]);
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        for voter in [accounts.bob, accounts.charlie] {
+            ink::env::test::set_caller(voter);
+            contract.vote(proposal_id, 0).unwrap();
+        }
+
+        // Blacklisted after voting: the weight stays where it was cast
+        ink::env::test::set_caller(accounts.alice);
+        contract.set_blacklisted(accounts.bob, true).unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        assert_eq!(contract.change_vote(proposal_id, 1), Err(Error::VoterBlacklisted));
+
+        // Deregistered after voting
+        ink::env::test::set_caller(accounts.charlie);
+        contract.deregister_voter().unwrap();
+        assert_eq!(contract.change_vote(proposal_id, 1), Err(Error::NotAuthorized));
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![2, 0]);
+    }
+
+    #[ink::test]
+    fn conviction_lock_outlasts_the_voting_period() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        contract.set_deposit_weighting(true).unwrap();
+        contract.register_voter().unwrap();
+        ink::env::test::set_caller(accounts.bob);
+        contract.register_voter().unwrap();
+        lock_deposit(&mut contract, 50).unwrap();
+
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Governance, default_governance_params());
+        contract.vote_with_conviction(proposal_id, 0, 4 * CONVICTION_PERIOD).unwrap();
+        let lock_until = contract.get_user_vote(proposal_id, accounts.bob).unwrap().lock_until;
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert!(contract.get_proposal(proposal_id).unwrap().voting_end < lock_until);
+
+        // Voting has closed, but the weight stays committed until the lock ends
+        assert_eq!(contract.unlock_weight(), Err(Error::VoteLocked));
+        assert_eq!(contract.delegate(accounts.charlie), Err(Error::VoteLocked));
+        assert_eq!(contract.deregister_voter(), Err(Error::VoteLocked));
+        ink::env::test::set_caller(accounts.alice);
+        assert_eq!(contract.set_voting_power(accounts.bob, 10), Err(Error::VoteLocked));
+        assert_eq!(contract.set_type_weight(accounts.bob, ProposalType::Treasury, 1), Err(Error::VoteLocked));
+        contract.set_voting_power(accounts.bob, 60).unwrap();
+
+        set_block(lock_until);
+        ink::env::test::set_caller(accounts.bob);
+        contract.unlock_weight().unwrap();
+        assert_eq!(contract.get_locked_deposit(accounts.bob), 0);
+        contract.deregister_voter().unwrap();
+    }
+
+    #[ink::test]
+    fn type_weights_prevent_early_resolution() {
+        let accounts = ink::env::test::default_accounts();
+        ink::env::test::set_caller(accounts.alice);
+        let mut contract = TreasuryGovernance::new();
+        let mut voters = vec![accounts.alice];
+        voters.extend((1..20u8).map(|n| ink::H160::from([n; 20])));
+        contract.register_voters(voters.clone()).unwrap();
+        let heavy = voters[19];
+        contract.set_type_weight(heavy, ProposalType::Technical, 100).unwrap();
+        contract.set_resolve_on_vote(true).unwrap();
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Technical, default_governance_params());
+
+        // Nineteen Yes votes would be decisive at weight 1 each
+        for &voter in &voters[..19] {
+            ink::env::test::set_caller(voter);
+            contract.vote(proposal_id, 0).unwrap();
+        }
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Active);
+        assert_eq!(contract.get_effective_status(proposal_id), Ok(ProposalStatus::Active));
+
+        // The heavy type-weighted voter can still outvote them
+        ink::env::test::set_caller(heavy);
+        contract.vote(proposal_id, 1).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().vote_counts, vec![19, 100]);
+    }
+
+    #[ink::test]
+    fn type_weights_count_toward_reachable_quorum() {
+        let accounts = ink::env::test::default_accounts();
+        let mut contract = weighted_trio([1, 1, 1]);
+        contract.set_type_weight(accounts.alice, ProposalType::Technical, 100).unwrap();
+        let mut params = default_governance_params();
+        params.min_absolute_weight = 50;
+        let proposal_id = create_test_proposal(&mut contract, ProposalType::Technical, params);
+
+        // General power totals 3, but alice alone carries 100 here
+        assert_eq!(contract.get_effective_status(proposal_id), Ok(ProposalStatus::Active));
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Active);
+
+        contract.vote(proposal_id, 0).unwrap();
+        advance_to_voting_end(&contract, proposal_id);
+        contract.update_proposal_status(proposal_id).unwrap();
+        assert_eq!(contract.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+    }
+}
+
+
+#[cfg(all(test, feature = "e2e-tests"))]
+mod e2e_tests {
+    use crate::treasury_governance::*;
+    use execution_target::{ExecutionTarget, ExecutionTargetRef};
+    use ink_e2e::{ChainBackend, ContractsBackend};
+
+    type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+    fn short_governance_params() -> GovernanceParameters {
+        GovernanceParameters {
+            voting_period: VotingPeriod::Custom(MIN_CUSTOM_VOTING_PERIOD),
+            quorum_threshold: QuorumThreshold::Ten,
+            execution_delay: ExecutionDelay::Immediately,
+            execution_window_blocks: 0,
+            min_absolute_weight: 0,
+            allow_payload_amendment: false,
+            voting_mode: VotingMode::Linear,
+            min_approval_bps: 0,
+        }
+    }
+
+    fn yes_no_options() -> VotingOptions {
+        VotingOptions {
+            options: vec!["Yes".to_string(), "No".to_string()],
+        }
+    }
+
+    #[ink_e2e::test]
+    async fn execution_hook_is_notified_with_the_proposal_id(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let mut constructor = TreasuryGovernanceRef::new();
+        let governance = client
+            .instantiate("treasury_governance", &ink_e2e::alice(), &mut constructor)
+            .submit()
+            .await
+            .expect("governance instantiate failed");
+        let mut governance_call = governance.call_builder::<TreasuryGovernance>();
+        let mut constructor = ExecutionTargetRef::new();
+        let hook = client
+            .instantiate("execution_target", &ink_e2e::alice(), &mut constructor)
+            .submit()
+            .await
+            .expect("hook instantiate failed");
+        let mut hook_call = hook.call_builder::<ExecutionTarget>();
+
+        client
+            .call(&ink_e2e::alice(), &governance_call.register_voter())
+            .submit()
+            .await
+            .expect("register failed");
+        // Open execution so Bob can execute as a keeper
+        client
+            .call(&ink_e2e::alice(), &governance_call.set_restrict_execution(false))
+            .submit()
+            .await
+            .expect("open execution failed");
+        client
+            .call(&ink_e2e::alice(), &governance_call.set_execution_hook(Some(hook.addr), true))
+            .submit()
+            .await
+            .expect("set hook failed");
+
+        // Two proposals pass together; the first executes, the second meets a failing hook
+        for proposal_id in [1, 2] {
+            let create = governance_call.create_proposal(
+                "Notify".to_string(),
+                "Hook integration".to_string(),
+                ProposalType::Governance,
+                short_governance_params(),
+                yes_no_options(),
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            let created = client
+                .call(&ink_e2e::alice(), &create)
+                .submit()
+                .await
+                .expect("create failed");
+            assert_eq!(created.return_value(), Ok(proposal_id));
+            client
+                .call(&ink_e2e::alice(), &governance_call.vote(proposal_id, 0))
+                .submit()
+                .await
+                .expect("vote failed");
+        }
+        for _ in 0..=MIN_CUSTOM_VOTING_PERIOD {
+            client
+                .runtime_call(&ink_e2e::bob(), "System", "remark", vec![ink_e2e::subxt::dynamic::Value::from_bytes(Vec::<u8>::new())])
+                .await
+                .expect("remark failed");
+        }
+        for proposal_id in [1, 2] {
+            client
+                .call(&ink_e2e::alice(), &governance_call.update_proposal_status(proposal_id))
+                .submit()
+                .await
+                .expect("status update failed");
+        }
+
+        // when
+        let executed = client
+            .call(&ink_e2e::bob(), &governance_call.execute_proposal(1))
+            .submit()
+            .await
+            .expect("execute failed");
+
+        // then
+        assert_eq!(executed.return_value(), Ok(()));
+        let notified = client.call(&ink_e2e::alice(), &hook_call.get_notified()).dry_run().await?;
+        assert_eq!(notified.return_value(), vec![1]);
+
+        // A required hook that fails reverts the execution
+        client
+            .call(&ink_e2e::alice(), &hook_call.set_reject_notifications(true))
+            .submit()
+            .await
+            .expect("hook update failed");
+        let rejected = client
+            .call(&ink_e2e::bob(), &governance_call.execute_proposal(2))
+            .submit()
+            .await
+            .expect("execute call failed");
+        assert_eq!(rejected.return_value(), Err(Error::HookFailed));
+        let proposal = client.call(&ink_e2e::alice(), &governance_call.get_proposal(2)).dry_run().await?;
+        assert_eq!(proposal.return_value().map(|proposal| proposal.status), Ok(ProposalStatus::Passed));
+        let queue = client.call(&ink_e2e::alice(), &governance_call.get_execution_queue()).dry_run().await?;
+        assert_eq!(queue.return_value(), vec![2]);
+
+        // The head could still run, so it cannot be expired yet
+        let expired = client
+            .call(&ink_e2e::alice(), &governance_call.expire_queue_head(2))
+            .submit()
+            .await
+            .expect("expire call failed");
+        assert_eq!(expired.return_value(), Err(Error::QueueHeadExecutable));
+
+        // Once the hook accepts again the head executes
+        client
+            .call(&ink_e2e::alice(), &hook_call.set_reject_notifications(false))
+            .submit()
+            .await
+            .expect("hook update failed");
+        let executed = client
+            .call(&ink_e2e::bob(), &governance_call.execute_proposal(2))
+            .submit()
+            .await
+            .expect("execute failed");
+        assert_eq!(executed.return_value(), Ok(()));
+        let queue = client.call(&ink_e2e::alice(), &governance_call.get_execution_queue()).dry_run().await?;
+        assert!(queue.return_value().is_empty());
+
+        Ok(())
+    }
+
+    #[ink_e2e::test]
+    async fn execution_call_cannot_reenter_governance(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let mut constructor = TreasuryGovernanceRef::new();
+        let governance = client
+            .instantiate("treasury_governance", &ink_e2e::alice(), &mut constructor)
+            .submit()
+            .await
+            .expect("governance instantiate failed");
+        let mut governance_call = governance.call_builder::<TreasuryGovernance>();
+        let mut constructor = ExecutionTargetRef::new();
+        let target = client
+            .instantiate("execution_target", &ink_e2e::alice(), &mut constructor)
+            .submit()
+            .await
+            .expect("target instantiate failed");
+        let target_call = target.call_builder::<ExecutionTarget>();
+
+        client
+            .call(&ink_e2e::alice(), &governance_call.register_voter())
+            .submit()
+            .await
+            .expect("register failed");
+        // Open execution so Bob can execute as a keeper
+        client
+            .call(&ink_e2e::alice(), &governance_call.set_restrict_execution(false))
+            .submit()
+            .await
+            .expect("open execution failed");
+
+        // Proposal 1 has the target execute it again, proposal 2 has it
+        // finalize proposal 3, and proposal 3 makes no call
+        let reentries = [
+            Some((ink::selector_bytes!("execute_proposal"), 1u32)),
+            Some((ink::selector_bytes!("update_proposal_status"), 3u32)),
+            None,
+        ];
+        for (proposal_id, reentry) in (1u32..).zip(reentries) {
+            let execution_call = reentry.map(|(selector, reentered_id)| ExecutionCall {
+                target: target.addr,
+                selector: ink::selector_bytes!("reenter"),
+                input: scale::Encode::encode(&(governance.addr, selector, reentered_id)),
+                gas_limit: u64::MAX,
+            });
+            let create = governance_call.create_proposal(
+                "Call out".to_string(),
+                "Target calls back in".to_string(),
+                ProposalType::Governance,
+                short_governance_params(),
+                yes_no_options(),
+                None,
+                None,
+                None,
+                execution_call,
+                None,
+            );
+            let created = client
+                .call(&ink_e2e::alice(), &create)
+                .submit()
+                .await
+                .expect("create failed");
+            assert_eq!(created.return_value(), Ok(proposal_id));
+            client
+                .call(&ink_e2e::alice(), &governance_call.vote(proposal_id, 0))
+                .submit()
+                .await
+                .expect("vote failed");
+        }
+        for _ in 0..=MIN_CUSTOM_VOTING_PERIOD {
+            client
+                .runtime_call(&ink_e2e::bob(), "System", "remark", vec![ink_e2e::subxt::dynamic::Value::from_bytes(Vec::<u8>::new())])
+                .await
+                .expect("remark failed");
+        }
+        for proposal_id in [1, 2] {
+            client
+                .call(&ink_e2e::alice(), &governance_call.update_proposal_status(proposal_id))
+                .submit()
+                .await
+                .expect("status update failed");
+        }
+
+        // when
+        for proposal_id in [1, 2] {
+            let executed = client
+                .call(&ink_e2e::bob(), &governance_call.execute_proposal(proposal_id))
+                .submit()
+                .await
+                .expect("execute failed");
+            assert_eq!(executed.return_value(), Ok(()));
+        }
+
+        // then
+        let reentries = client.call(&ink_e2e::alice(), &target_call.get_reentry_results()).dry_run().await?;
+        let reentrancy_detected = scale::Encode::encode(&Error::ReentrancyDetected)[0];
+        assert_eq!(reentries.return_value(), vec![Err(reentrancy_detected), Err(reentrancy_detected)]);
+        let proposal = client.call(&ink_e2e::alice(), &governance_call.get_proposal(1)).dry_run().await?;
+        assert_eq!(proposal.return_value().map(|proposal| proposal.status), Ok(ProposalStatus::Executed));
+
+        // The refused nested update left proposal 3 to finalize normally
+        let proposal = client.call(&ink_e2e::alice(), &governance_call.get_proposal(3)).dry_run().await?;
+        assert_eq!(proposal.return_value().map(|proposal| proposal.status), Ok(ProposalStatus::Active));
+        client
+            .call(&ink_e2e::alice(), &governance_call.update_proposal_status(3))
+            .submit()
+            .await
+            .expect("status update failed");
+        let queue = client.call(&ink_e2e::alice(), &governance_call.get_execution_queue()).dry_run().await?;
+        assert_eq!(queue.return_value(), vec![3]);
+
+        Ok(())
+    }
+}